@@ -0,0 +1,157 @@
+use crate::bitboard::{king_attacks, square_bit, squares, Bitboard};
+use crate::piece::{Piece, PieceColour, PieceType};
+use crate::position::{remove_rook_castling_rights, Move, MoveFlag, Position};
+
+pub type Explosion = [Piece; 9]; // pieces blown up by a capture, one per square around it (rows from a8 side, empty if nothing went)
+
+fn explosion_squares(index: i32) -> [i32; 9] {
+    // returns the capture square and the squares around it in a fixed order (-1 for squares off the board)
+    let mut blast = [-1; 9];
+    for (blast_index, (rank_step, file_step)) in (-1..=1)
+        .flat_map(|rank_step| (-1..=1).map(move |file_step| (rank_step, file_step)))
+        .enumerate()
+    {
+        let rank = index / 8 + rank_step;
+        let file = index % 8 + file_step;
+        if (0..8).contains(&rank) && (0..8).contains(&file) {
+            blast[blast_index] = rank * 8 + file;
+        }
+    }
+    blast
+}
+
+pub(crate) fn is_atomic_capture(position: &Position, piece_move: Move) -> bool {
+    // returns true if the move takes a piece (and so causes an explosion)
+    match piece_move.flag {
+        MoveFlag::EnPassant => true,
+        MoveFlag::Castling | MoveFlag::Drop(_) => false,
+        _ => position.board[piece_move.end as usize].piece_type != PieceType::None,
+    }
+}
+
+pub(crate) fn kings_touching(king_indexes: (i32, i32)) -> bool {
+    // returns true if the kings are next to each other (neither can be checked, taking one would blow up the other)
+    let (white_king, black_king) = king_indexes;
+    white_king != -1 && black_king != -1 && king_attacks(white_king) & square_bit(black_king) != 0
+}
+
+pub(crate) fn atomic_king_safe_after(position: &Position, sudo_move: Move) -> bool {
+    // returns true if the move is allowed in atomic chess (it doesn't blow up the player's own king or leave it in check,
+    // unless the other king is blown up first), kings can't take anything
+    let colour = position.colours_turn;
+    let moving_piece = position.board[sudo_move.start as usize];
+    let captured_piece = position.board[sudo_move.end as usize];
+    let is_capture = is_atomic_capture(position, sudo_move);
+
+    if moving_piece.piece_type == PieceType::King && is_capture {
+        return false;
+    }
+
+    if sudo_move.flag == MoveFlag::Castling {
+        return true; // already checked when generating castling
+    }
+
+    let mut king_indexes = position.king_indexes;
+    if moving_piece.piece_type == PieceType::King {
+        match colour {
+            PieceColour::White => king_indexes.0 = sudo_move.end,
+            _ => king_indexes.1 = sudo_move.end,
+        }
+    }
+
+    // sudo make move on a copy of the bitboards
+    let mut bitboards = position.bitboards;
+    bitboards.toggle(sudo_move.start, moving_piece);
+    bitboards.toggle(sudo_move.end, captured_piece);
+    bitboards.toggle(sudo_move.end, moving_piece);
+
+    if is_capture {
+        if sudo_move.flag == MoveFlag::EnPassant {
+            let taken_index = match colour {
+                PieceColour::White => sudo_move.end + 8,
+                _ => sudo_move.end - 8,
+            };
+            bitboards.toggle(taken_index, position.board[taken_index as usize]);
+        }
+
+        bitboards.toggle(sudo_move.end, moving_piece); // the capturing piece goes up too
+        let blast: Bitboard = king_attacks(sudo_move.end) & bitboards.occupied;
+        for index in squares(blast) {
+            let piece = position.board[index as usize];
+            if piece.piece_type != PieceType::Pawn {
+                bitboards.toggle(index, piece);
+                if piece.piece_type == PieceType::King {
+                    match piece.piece_colour {
+                        PieceColour::White => king_indexes.0 = -1,
+                        _ => king_indexes.1 = -1,
+                    }
+                }
+            }
+        }
+    }
+
+    let (own_king, enemy_king) = match colour {
+        PieceColour::White => king_indexes,
+        _ => (king_indexes.1, king_indexes.0),
+    };
+
+    if own_king == -1 {
+        return false; // blew up its own king (or it was already blown up and the game is over)
+    }
+    if enemy_king == -1 || kings_touching(king_indexes) {
+        return true; // won the game, or the kings protect each other
+    }
+
+    !bitboards.is_attacked(own_king, colour.opponent())
+}
+
+impl Position {
+    pub(crate) fn explode(&mut self, index: i32) -> Explosion {
+        // blows up the capturing piece on the square and every piece but pawns around it, returns what was blown up
+        let mut explosion: Explosion = [Piece::EMPTY; 9];
+
+        for (blast_index, blast_square) in explosion_squares(index).into_iter().enumerate() {
+            if blast_square == -1 {
+                continue;
+            }
+
+            let piece = self.board[blast_square as usize];
+            if piece.piece_type == PieceType::None
+                || (piece.piece_type == PieceType::Pawn && blast_square != index)
+            {
+                continue; // pawns only go if they did the capturing
+            }
+
+            explosion[blast_index] = piece;
+            self.set_piece(blast_square, Piece::EMPTY);
+            match (piece.piece_type, piece.piece_colour) {
+                (PieceType::King, PieceColour::White) => {
+                    self.king_indexes.0 = -1;
+                    (self.castling_rights.0, self.castling_rights.1) = (false, false);
+                }
+                (PieceType::King, _) => {
+                    self.king_indexes.1 = -1;
+                    (self.castling_rights.2, self.castling_rights.3) = (false, false);
+                }
+                (PieceType::Rook, colour) => remove_rook_castling_rights(
+                    &mut self.castling_rights,
+                    self.castling_rooks,
+                    blast_square,
+                    colour,
+                ),
+                _ => (),
+            }
+        }
+
+        explosion
+    }
+
+    pub(crate) fn unexplode(&mut self, index: i32, explosion: Explosion) {
+        // puts back the pieces an explosion blew up (unmake_move puts the king indexes back)
+        for (blast_square, piece) in explosion_squares(index).into_iter().zip(explosion) {
+            if blast_square != -1 && piece.piece_type != PieceType::None {
+                self.set_piece(blast_square, piece);
+            }
+        }
+    }
+}
@@ -0,0 +1,252 @@
+use crate::piece::{Piece, PieceColour, PieceType};
+
+pub type Bitboard = u64; // one bit per square (bit 0 = a8, bit 63 = h1, same as board indexes)
+
+pub const fn square_bit(index: i32) -> Bitboard {
+    // returns bitboard with only the given square set
+    1u64 << index
+}
+
+pub fn squares(mut bitboard: Bitboard) -> impl Iterator<Item = i32> {
+    // iterates over the indexes of the set squares (lowest index first)
+    std::iter::from_fn(move || {
+        if bitboard == 0 {
+            return None;
+        }
+        let index = bitboard.trailing_zeros() as i32;
+        bitboard &= bitboard - 1; // clear lowest set bit
+        Some(index)
+    })
+}
+
+pub fn colour_index(piece_colour: PieceColour) -> usize {
+    // index of a colour in the bitboard tables (None is never stored)
+    match piece_colour {
+        PieceColour::White => 0,
+        PieceColour::Black => 1,
+        PieceColour::None => 2,
+    }
+}
+
+pub fn type_index(piece_type: PieceType) -> usize {
+    // index of a piece type in the bitboard tables (None is never stored)
+    match piece_type {
+        PieceType::Pawn => 0,
+        PieceType::Rook => 1,
+        PieceType::Knight => 2,
+        PieceType::Bishop => 3,
+        PieceType::Queen => 4,
+        PieceType::King => 5,
+        PieceType::None => 6,
+    }
+}
+
+// ------- ATTACK TABLES -------
+
+const fn step_attacks(index: i32, steps: &[(i32, i32)]) -> Bitboard {
+    // squares reached by single (file, rank) steps that stay on the board
+    let mut attacks = 0;
+    let mut i = 0;
+    while i < steps.len() {
+        let new_file = index % 8 + steps[i].0;
+        let new_rank = index / 8 + steps[i].1;
+        if new_file >= 0 && new_file <= 7 && new_rank >= 0 && new_rank <= 7 {
+            attacks |= square_bit(new_file + new_rank * 8);
+        }
+        i += 1;
+    }
+    attacks
+}
+
+const fn step_table(steps: &[(i32, i32)]) -> [Bitboard; 64] {
+    // step attacks from every square
+    let mut table = [0; 64];
+    let mut index = 0;
+    while index < 64 {
+        table[index as usize] = step_attacks(index, steps);
+        index += 1;
+    }
+    table
+}
+
+const KNIGHT_STEPS: [(i32, i32); 8] = [
+    (-2, -1),
+    (-1, -2),
+    (1, -2),
+    (2, -1),
+    (2, 1),
+    (1, 2),
+    (-1, 2),
+    (-2, 1),
+];
+const KING_STEPS: [(i32, i32); 8] = [
+    (-1, -1),
+    (0, -1),
+    (1, -1),
+    (-1, 0),
+    (1, 0),
+    (-1, 1),
+    (0, 1),
+    (1, 1),
+];
+
+const KNIGHT_ATTACKS: [Bitboard; 64] = step_table(&KNIGHT_STEPS);
+const KING_ATTACKS: [Bitboard; 64] = step_table(&KING_STEPS);
+const PAWN_ATTACKS: [[Bitboard; 64]; 2] = [
+    step_table(&[(-1, -1), (1, -1)]), // white pawns take up the board
+    step_table(&[(-1, 1), (1, 1)]),   // black pawns take down the board
+];
+
+// slider directions as (file, rank) steps: south north east west south-east north-west south-west north-east
+const DIRECTIONS: [(i32, i32); 8] = [
+    (0, 1),
+    (0, -1),
+    (1, 0),
+    (-1, 0),
+    (1, 1),
+    (-1, -1),
+    (-1, 1),
+    (1, -1),
+];
+
+const fn ray_table() -> [[Bitboard; 64]; 8] {
+    // squares from each square to the edge of the board in each direction (not including the square)
+    let mut table = [[0; 64]; 8];
+    let mut dir = 0;
+    while dir < 8 {
+        let mut index = 0;
+        while index < 64 {
+            let mut file = index % 8 + DIRECTIONS[dir].0;
+            let mut rank = index / 8 + DIRECTIONS[dir].1;
+            while file >= 0 && file <= 7 && rank >= 0 && rank <= 7 {
+                table[dir][index as usize] |= square_bit(file + rank * 8);
+                file += DIRECTIONS[dir].0;
+                rank += DIRECTIONS[dir].1;
+            }
+            index += 1;
+        }
+        dir += 1;
+    }
+    table
+}
+
+const RAYS: [[Bitboard; 64]; 8] = ray_table();
+
+fn ray_attacks(index: i32, dir: usize, occupied: Bitboard) -> Bitboard {
+    // squares a slider attacks in one direction, stopping at (and including) the first piece in the way
+    let ray = RAYS[dir][index as usize];
+    let blockers = ray & occupied;
+
+    if blockers == 0 {
+        return ray;
+    }
+
+    let first_blocker = match DIRECTIONS[dir].0 + DIRECTIONS[dir].1 * 8 > 0 {
+        true => blockers.trailing_zeros(), // direction goes up the indexes
+        false => 63 - blockers.leading_zeros(), // direction goes down the indexes
+    };
+
+    ray ^ RAYS[dir][first_blocker as usize]
+}
+
+pub fn knight_attacks(index: i32) -> Bitboard {
+    KNIGHT_ATTACKS[index as usize]
+}
+
+pub fn king_attacks(index: i32) -> Bitboard {
+    KING_ATTACKS[index as usize]
+}
+
+pub fn pawn_attacks(index: i32, piece_colour: PieceColour) -> Bitboard {
+    // squares a pawn of the given colour attacks from the square
+    match piece_colour {
+        PieceColour::None => 0,
+        _ => PAWN_ATTACKS[colour_index(piece_colour)][index as usize],
+    }
+}
+
+pub fn rook_attacks(index: i32, occupied: Bitboard) -> Bitboard {
+    (0..4).fold(0, |attacks, dir| {
+        attacks | ray_attacks(index, dir, occupied)
+    })
+}
+
+pub fn bishop_attacks(index: i32, occupied: Bitboard) -> Bitboard {
+    (4..8).fold(0, |attacks, dir| {
+        attacks | ray_attacks(index, dir, occupied)
+    })
+}
+
+pub fn queen_attacks(index: i32, occupied: Bitboard) -> Bitboard {
+    rook_attacks(index, occupied) | bishop_attacks(index, occupied)
+}
+
+// ------- BITBOARD POSITION -------
+
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq)]
+pub struct Bitboards {
+    // where each kind of piece is (kept alongside the board by Position)
+    pub pieces: [[Bitboard; 6]; 2], // [colour][piece type]
+    pub colours: [Bitboard; 2],     // all pieces of each colour
+    pub occupied: Bitboard,         // all pieces
+}
+
+impl Bitboards {
+    pub fn from_board(board: &[Piece]) -> Bitboards {
+        // builds bitboards from a board layout
+        let mut bitboards = Bitboards::default();
+
+        for (index, piece) in board.iter().enumerate() {
+            bitboards.toggle(index as i32, *piece);
+        }
+
+        bitboards
+    }
+
+    pub fn toggle(&mut self, index: i32, piece: Piece) {
+        // adds a piece to a square (or takes it away if it is already there)
+        if piece.piece_type == PieceType::None || piece.piece_colour == PieceColour::None {
+            return;
+        }
+
+        let bit = square_bit(index);
+        let colour = colour_index(piece.piece_colour);
+
+        self.pieces[colour][type_index(piece.piece_type)] ^= bit;
+        self.colours[colour] ^= bit;
+        self.occupied ^= bit;
+    }
+
+    pub fn piece_bitboard(&self, piece_type: PieceType, piece_colour: PieceColour) -> Bitboard {
+        // returns squares holding pieces of the given type and colour
+        if piece_type == PieceType::None || piece_colour == PieceColour::None {
+            return 0;
+        }
+
+        self.pieces[colour_index(piece_colour)][type_index(piece_type)]
+    }
+
+    pub fn attackers_to(&self, index: i32, occupied: Bitboard) -> Bitboard {
+        // returns pieces of both colours attacking a square (sliders see through squares not in occupied)
+        let rooks_queens =
+            self.pieces[0][1] | self.pieces[0][4] | self.pieces[1][1] | self.pieces[1][4];
+        let bishops_queens =
+            self.pieces[0][3] | self.pieces[0][4] | self.pieces[1][3] | self.pieces[1][4];
+
+        (pawn_attacks(index, PieceColour::Black) & self.pieces[0][0]) // white pawns attack like a black pawn looks
+            | (pawn_attacks(index, PieceColour::White) & self.pieces[1][0])
+            | (knight_attacks(index) & (self.pieces[0][2] | self.pieces[1][2]))
+            | (king_attacks(index) & (self.pieces[0][5] | self.pieces[1][5]))
+            | (rook_attacks(index, occupied) & rooks_queens)
+            | (bishop_attacks(index, occupied) & bishops_queens)
+    }
+
+    pub fn is_attacked(&self, index: i32, attacking_side: PieceColour) -> bool {
+        // returns true if any piece of the attacking side attacks the square
+        if attacking_side == PieceColour::None {
+            return false;
+        }
+
+        self.attackers_to(index, self.occupied) & self.colours[colour_index(attacking_side)] != 0
+    }
+}
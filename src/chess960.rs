@@ -0,0 +1,69 @@
+use crate::position::Position;
+
+pub const CHESS960_POSITIONS: u32 = 960; // start positions are numbered 0 to 959
+pub const STANDARD_NUMBER: u32 = 518; // number of the usual start position
+
+// squares the knights take out of the five left once the bishops and queen are placed
+const KNIGHT_PLACEMENTS: [(usize, usize); 10] = [
+    (0, 1),
+    (0, 2),
+    (0, 3),
+    (0, 4),
+    (1, 2),
+    (1, 3),
+    (1, 4),
+    (2, 3),
+    (2, 4),
+    (3, 4),
+];
+
+fn empty_files(rank: &[char; 8]) -> Vec<usize> {
+    // returns the files with nothing placed on them yet
+    (0..8).filter(|&file| rank[file] == ' ').collect()
+}
+
+pub fn back_rank(number: u32) -> Option<String> {
+    // returns white's back rank pieces from a to h for a start position number (Scharnagl's numbering, e.g: 518 -> RNBQKBNR)
+    if number >= CHESS960_POSITIONS {
+        return None;
+    }
+
+    let mut rank = [' '; 8];
+    let mut number = number as usize;
+
+    rank[(number % 4) * 2 + 1] = 'B'; // light squared bishop (b, d, f or h file)
+    number /= 4;
+    rank[(number % 4) * 2] = 'B'; // dark squared bishop (a, c, e or g file)
+    number /= 4;
+
+    rank[empty_files(&rank)[number % 6]] = 'Q';
+    number /= 6;
+
+    let (first_knight, second_knight) = KNIGHT_PLACEMENTS[number];
+    let files = empty_files(&rank);
+    rank[files[first_knight]] = 'N';
+    rank[files[second_knight]] = 'N';
+
+    // the king always goes between the rooks
+    for (file, piece) in empty_files(&rank).into_iter().zip(['R', 'K', 'R']) {
+        rank[file] = piece;
+    }
+
+    Some(rank.iter().collect())
+}
+
+impl Position {
+    pub fn chess960(number: u32) -> Option<Position> {
+        // returns the chess960 start position with the given number (None if it isn't 0 to 959)
+        let back_rank = back_rank(number)?;
+        let fen_string = format!(
+            "{}/pppppppp/8/8/8/8/PPPPPPPP/{} w KQkq - 0 1",
+            back_rank.to_lowercase(),
+            back_rank
+        );
+
+        let mut position = Position::from_fen(&fen_string).ok()?;
+        position.chess960 = true; // number 518 looks like a normal game but castles like chess960
+        Some(position)
+    }
+}
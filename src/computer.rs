@@ -0,0 +1,126 @@
+use std::sync::atomic::AtomicBool;
+use std::time::Duration;
+
+use crate::draw::DrawReason;
+use crate::game::Game;
+use crate::player::{Player, PlayerAction, RandomPlayer};
+use crate::position::Move;
+use crate::search::{self, SearchLimits, SearchStyle};
+
+pub const MAX_LEVEL: u32 = 10; // plays as well as it can (searching as deep as the limits allow)
+const DEFAULT_MOVE_TIME: Duration = Duration::from_secs(1); // thinking time when no limit is given
+
+#[derive(Debug, Clone)]
+pub struct ComputerPlayer {
+    // searches for its moves, lower levels look fewer moves ahead and sometimes play a random move instead
+    level: u32, // 1 (beginner) to MAX_LEVEL
+    limits: SearchLimits,
+    style: SearchStyle,
+    random_mover: RandomPlayer, // plays the random moves (and makes the choice to)
+}
+
+impl ComputerPlayer {
+    pub fn new(level: u32, limits: SearchLimits, style: SearchStyle) -> ComputerPlayer {
+        // returns a computer player of the given level, thinking for as long as the limits allow
+        ComputerPlayer::with_random_mover(level, limits, style, RandomPlayer::new())
+    }
+
+    pub fn with_seed(
+        level: u32,
+        limits: SearchLimits,
+        style: SearchStyle,
+        seed: u64,
+    ) -> ComputerPlayer {
+        // returns a computer player that always makes the same choices for the same seed
+        ComputerPlayer::with_random_mover(level, limits, style, RandomPlayer::with_seed(seed))
+    }
+
+    fn with_random_mover(
+        level: u32,
+        mut limits: SearchLimits,
+        style: SearchStyle,
+        random_mover: RandomPlayer,
+    ) -> ComputerPlayer {
+        // returns a computer player (levels are kept in range and a search with no limit gets a time limit)
+        limits.infinite = false; // nobody is there to say stop
+        let has_limit = limits.depth.is_some()
+            || limits.nodes.is_some()
+            || limits.move_time.is_some()
+            || limits.time_left != (None, None);
+        if !has_limit {
+            limits.move_time = Some(DEFAULT_MOVE_TIME);
+        }
+
+        ComputerPlayer {
+            level: level.clamp(1, MAX_LEVEL),
+            limits,
+            style,
+            random_mover,
+        }
+    }
+
+    pub fn level(&self) -> u32 {
+        // returns how strong the player is (1 to MAX_LEVEL)
+        self.level
+    }
+
+    pub fn random_move_chance(&self) -> u32 {
+        // returns the percentage of moves played at random instead of searched for (90% at level 1, none at MAX_LEVEL)
+        (MAX_LEVEL - self.level) * 100 / MAX_LEVEL
+    }
+
+    pub fn search_limits(&self) -> SearchLimits {
+        // returns the limits searches are made with (the level caps the depth below MAX_LEVEL)
+        let mut limits = self.limits;
+        if self.level < MAX_LEVEL {
+            limits.depth = Some(
+                limits
+                    .depth
+                    .map_or(self.level, |depth| depth.min(self.level)),
+            );
+        }
+        limits
+    }
+
+    fn thinks_draw_is_good(&self, own_score: i32) -> bool {
+        // returns true if a draw is worth at least as much to the computer as the position (contempt makes draws worth less)
+        own_score <= -self.style.contempt
+    }
+}
+
+impl Player for ComputerPlayer {
+    fn name(&self) -> String {
+        format!("Computer (level {})", self.level)
+    }
+
+    fn choose_action(&mut self, game: &Game, legal_moves: &[Move]) -> PlayerAction {
+        if legal_moves.is_empty() {
+            return PlayerAction::Resign; // shouldn't be asked to move when the game is over
+        }
+
+        if self.random_mover.next_random() % 100 < self.random_move_chance() as u64 {
+            return self.random_mover.choose_action(game, legal_moves);
+        }
+
+        let result = search::search_game_with_style(
+            game,
+            &self.search_limits(),
+            &self.style,
+            &AtomicBool::new(false),
+            |_| {},
+        );
+        match result.best_move {
+            Some(best_move) => PlayerAction::Move(best_move),
+            None => self.random_mover.choose_action(game, legal_moves),
+        }
+    }
+
+    fn accepts_draw(&mut self, game: &Game) -> bool {
+        // offers are made on the opponent's turn, so the score is flipped to the computer's side
+        self.thinks_draw_is_good(-game.position.evaluate())
+    }
+
+    fn claims_draw(&mut self, game: &Game, _draw_reason: DrawReason) -> bool {
+        self.thinks_draw_is_good(game.position.evaluate())
+    }
+}
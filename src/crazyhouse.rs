@@ -0,0 +1,105 @@
+use crate::bitboard::{colour_index, square_bit, type_index};
+use crate::evaluation::piece_value;
+use crate::piece::{Piece, PieceColour, PieceType};
+use crate::position::{Move, MoveFlag, Position};
+use crate::zobrist;
+
+pub type Pockets = [[u32; 5]; 2]; // pieces held by white and black (pawn, rook, knight, bishop, queen counts)
+
+// pieces that can be held, in the order they are written in fens and shown next to the board
+pub const POCKET_ORDER: [PieceType; 5] = [
+    PieceType::Queen,
+    PieceType::Rook,
+    PieceType::Bishop,
+    PieceType::Knight,
+    PieceType::Pawn,
+];
+
+impl Position {
+    pub fn pocket_count(&self, colour: PieceColour, piece_type: PieceType) -> u32 {
+        // returns how many pieces of a type a colour holds ready to drop
+        match (colour, piece_type) {
+            (PieceColour::None, _) | (_, PieceType::King | PieceType::None) => 0,
+            _ => self.pockets[colour_index(colour)][type_index(piece_type)],
+        }
+    }
+
+    pub fn pocket_string(&self, colour: PieceColour) -> String {
+        // returns the fen letters of the pieces a colour holds (e.g: QNPP, empty if it holds none)
+        let mut pocket = String::new();
+
+        for piece_type in POCKET_ORDER {
+            let piece = Piece {
+                piece_type,
+                piece_colour: colour,
+            };
+            if let Some(fen_char) = piece.fen_char() {
+                for _ in 0..self.pocket_count(colour, piece_type) {
+                    pocket.push(fen_char);
+                }
+            }
+        }
+
+        pocket
+    }
+
+    pub(crate) fn pocket_value(&self, colour: PieceColour) -> i32 {
+        // returns the worth of the pieces a colour holds in centipawns
+        POCKET_ORDER
+            .iter()
+            .map(|&piece_type| {
+                piece_value(piece_type) * self.pocket_count(colour, piece_type) as i32
+            })
+            .sum()
+    }
+
+    pub(crate) fn crazyhouse_move(&mut self, piece_move: Move, captured_piece: Piece) {
+        // fills and empties the pockets and keeps track of promoted pieces for a move (before the pieces are moved)
+        let colour = self.colours_turn;
+        let start_bit = square_bit(piece_move.start);
+        let end_bit = square_bit(piece_move.end);
+
+        // a taken piece changes sides, promoted pieces go back to being pawns
+        let pocket_type = match piece_move.flag {
+            MoveFlag::EnPassant => PieceType::Pawn,
+            MoveFlag::Castling => PieceType::None, // end square can be the king's own rook
+            _ if self.promoted & end_bit != 0 => PieceType::Pawn,
+            _ => captured_piece.piece_type,
+        };
+        if pocket_type != PieceType::None {
+            self.change_pocket(colour, pocket_type, 1);
+        }
+
+        if let MoveFlag::Drop(piece_type) = piece_move.flag {
+            self.change_pocket(colour, piece_type, -1);
+            return;
+        }
+
+        let stays_promoted = self.promoted & start_bit != 0
+            || matches!(
+                piece_move.flag,
+                MoveFlag::RookPromo
+                    | MoveFlag::KnightPromo
+                    | MoveFlag::BishopPromo
+                    | MoveFlag::QueenPromo
+                    | MoveFlag::KingPromo
+            );
+
+        let old_promoted = self.promoted;
+        self.promoted &= !(start_bit | end_bit);
+        if stays_promoted {
+            self.promoted |= end_bit;
+        }
+        self.hash ^= zobrist::promoted_key(old_promoted) ^ zobrist::promoted_key(self.promoted);
+    }
+
+    fn change_pocket(&mut self, colour: PieceColour, piece_type: PieceType, change: i32) {
+        // adds (or takes away) pieces of a type from a colour's pocket, keeping the hash up to date
+        let count = &mut self.pockets[colour_index(colour)][type_index(piece_type)];
+        let old_count = *count;
+        *count = count.saturating_add_signed(change);
+
+        self.hash ^= zobrist::pocket_key(colour, piece_type, old_count)
+            ^ zobrist::pocket_key(colour, piece_type, *count);
+    }
+}
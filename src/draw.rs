@@ -0,0 +1,148 @@
+use crate::piece::{PieceColour, PieceType};
+use crate::position::{MoveFlag, Position};
+use crate::status::GameStatus;
+use crate::variant::Variant;
+use crate::zobrist;
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum DrawReason {
+    // ways a game can be drawn (other than stalemate)
+    FiftyMoveRule, // claimable after 50 moves by each side without a capture or pawn move
+    SeventyFiveMoveRule, // automatic after 75 moves by each side
+    ThreefoldRepetition, // claimable when the same position occurs for the third time
+    FivefoldRepetition, // automatic when the same position occurs for the fifth time
+    InsufficientMaterial, // neither side can ever checkmate
+    Agreement,     // both players agreed to a draw
+    VariantRule, // the variant's own rules call it a draw (e.g: both kings reaching the eighth rank)
+}
+
+impl Position {
+    pub fn is_insufficient_material(&self) -> bool {
+        // returns true if no sequence of legal moves could lead to checkmate (dead position)
+        match self.variant {
+            Variant::Standard => (),
+            Variant::ThreeCheck | Variant::Atomic => {
+                // any piece can give check (or blow up the king)
+                return self
+                    .board
+                    .iter()
+                    .all(|piece| matches!(piece.piece_type, PieceType::King | PieceType::None));
+            }
+            Variant::KingOfTheHill | Variant::RacingKings => return false, // kings can still race
+            Variant::Antichess => return false, // the game is won by losing pieces
+            Variant::Crazyhouse => {
+                if self.pockets.iter().flatten().any(|&count| count > 0) {
+                    return false; // held pieces can be dropped to mate
+                }
+            }
+        }
+
+        let mut knights = 0;
+        let mut bishop_square_colours: Vec<i32> = Vec::new(); // colour of the square each bishop stands on
+
+        for (index, piece) in self.board.iter().enumerate() {
+            match piece.piece_type {
+                PieceType::Pawn | PieceType::Rook | PieceType::Queen => return false, // mating material
+                PieceType::Knight => knights += 1,
+                PieceType::Bishop => {
+                    bishop_square_colours.push(((index / 8) + (index % 8)) as i32 % 2)
+                }
+                PieceType::King | PieceType::None => (),
+            }
+        }
+
+        if knights > 0 {
+            // a lone knight can't mate, a knight with any other minor piece might
+            return knights + bishop_square_colours.len() <= 1;
+        }
+
+        // bishops can only mate if there are bishops on both colours of square
+        bishop_square_colours
+            .windows(2)
+            .all(|pair| pair[0] == pair[1])
+    }
+
+    pub fn en_passant_capturable(&self) -> bool {
+        // returns true if the player to move can actually take en passant (a target square alone isn't enough)
+        self.en_passant_move != -1
+            && self
+                .legal_moves()
+                .iter()
+                .any(|legal_move| legal_move.flag == MoveFlag::EnPassant)
+    }
+
+    pub fn repetition_hash(&self) -> u64 {
+        // returns a key for the parts of the position that decide if two positions are the same (FIDE 9.2.3)
+        let en_passant_key = zobrist::en_passant_key(self);
+
+        if en_passant_key != 0 && !self.en_passant_capturable() {
+            return self.hash ^ en_passant_key; // en passant only counts if it can be played
+        }
+
+        self.hash
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct PositionHistory {
+    // every position reached in a game (for repetition detection)
+    keys: Vec<u64>,
+}
+
+impl PositionHistory {
+    pub fn new(position: &Position) -> PositionHistory {
+        // starts a history at the given position
+        PositionHistory {
+            keys: vec![position.repetition_hash()],
+        }
+    }
+
+    pub fn push(&mut self, position: &Position) {
+        // records a position reached after a move
+        self.keys.push(position.repetition_hash());
+    }
+
+    pub fn pop(&mut self) {
+        // forgets the last position recorded
+        self.keys.pop();
+    }
+
+    pub fn keys(&self) -> &[u64] {
+        // returns the repetition hash of every position recorded, oldest first
+        &self.keys
+    }
+
+    pub fn repetitions(&self, position: &Position) -> usize {
+        // returns how many times the position has occurred in the game
+        let key = position.repetition_hash();
+
+        self.keys.iter().filter(|&&other| other == key).count()
+    }
+
+    pub fn status(&self, position: &Position) -> GameStatus {
+        // returns the game status including draws that need the history to spot
+        match position.status() {
+            GameStatus::Ongoing { .. } if self.repetitions(position) >= 5 => {
+                GameStatus::Draw(DrawReason::FivefoldRepetition)
+            }
+            status => status,
+        }
+    }
+
+    pub fn claimable_draw(&self, position: &Position) -> Option<DrawReason> {
+        // returns a draw the player to move may claim (None if there isn't one)
+        if position.colours_turn == PieceColour::None {
+            return None;
+        }
+
+        if self.repetitions(position) >= 3 {
+            return Some(DrawReason::ThreefoldRepetition);
+        }
+
+        if position.halfmove_clock >= 100 {
+            return Some(DrawReason::FiftyMoveRule);
+        }
+
+        None
+    }
+}
@@ -0,0 +1,140 @@
+use crate::bitboard::{colour_index, square_bit, squares, type_index, Bitboard};
+use crate::evaluation::piece_value;
+use crate::piece::{PieceColour, PieceType};
+use crate::position::{Move, MoveFlag, Position};
+
+const KING_VALUE: i32 = 20_000; // taking the king ends any exchange
+const MAX_EXCHANGE: usize = 32; // captures in the longest possible exchange (every piece on the board)
+
+// least valuable attackers first
+const ATTACKER_ORDER: [PieceType; 6] = [
+    PieceType::Pawn,
+    PieceType::Knight,
+    PieceType::Bishop,
+    PieceType::Rook,
+    PieceType::Queen,
+    PieceType::King,
+];
+
+fn exchange_value(piece_type: PieceType) -> i32 {
+    // returns the worth of a piece when working out exchanges
+    match piece_type {
+        PieceType::King => KING_VALUE,
+        _ => piece_value(piece_type),
+    }
+}
+
+fn promotion_type(flag: MoveFlag) -> Option<PieceType> {
+    // returns the piece a pawn becomes with the move (None if it isn't a promotion)
+    match flag {
+        MoveFlag::QueenPromo | MoveFlag::Promotion => Some(PieceType::Queen),
+        MoveFlag::RookPromo => Some(PieceType::Rook),
+        MoveFlag::BishopPromo => Some(PieceType::Bishop),
+        MoveFlag::KnightPromo => Some(PieceType::Knight),
+        MoveFlag::KingPromo => Some(PieceType::King),
+        _ => None,
+    }
+}
+
+impl Position {
+    pub fn captured_piece(&self, piece_move: Move) -> PieceType {
+        // returns the type of piece the move takes (None if it isn't a capture)
+        match piece_move.flag {
+            MoveFlag::EnPassant => PieceType::Pawn,
+            MoveFlag::Castling => PieceType::None,
+            _ => self.board[piece_move.end as usize].piece_type,
+        }
+    }
+
+    pub fn static_exchange(&self, piece_move: Move) -> i32 {
+        // returns the material won (negative if lost) by the move once every profitable recapture on its end square has been made
+        // (pieces lined up behind each other join in as the ones in front are used, pins are ignored)
+        let target = piece_move.end;
+        let bitboards = &self.bitboards;
+        let mut colour = self.colours_turn;
+
+        let mut occupied: Bitboard = bitboards.occupied ^ square_bit(piece_move.start);
+        if piece_move.flag == MoveFlag::EnPassant {
+            let taken_index = match colour {
+                PieceColour::White => target + 8,
+                _ => target - 8,
+            };
+            occupied ^= square_bit(taken_index);
+        }
+
+        let mut gains = [0; MAX_EXCHANGE];
+        gains[0] = piece_value(self.captured_piece(piece_move));
+        let mut piece_on_target = self.board[piece_move.start as usize].piece_type; // piece that can be taken next
+
+        if let Some(promoted) = promotion_type(piece_move.flag) {
+            gains[0] += piece_value(promoted) - piece_value(PieceType::Pawn);
+            piece_on_target = promoted;
+        }
+
+        let mut depth = 0;
+        loop {
+            colour = colour.opponent();
+            let attackers = bitboards.attackers_to(target, occupied)
+                & occupied
+                & bitboards.colours[colour_index(colour)];
+
+            let Some((attacker_type, attacker_index)) =
+                ATTACKER_ORDER.iter().find_map(|&piece_type| {
+                    let pieces =
+                        attackers & bitboards.pieces[colour_index(colour)][type_index(piece_type)];
+                    squares(pieces).next().map(|index| (piece_type, index))
+                })
+            else {
+                break; // nobody left to recapture
+            };
+
+            if attacker_type == PieceType::King {
+                let defenders = bitboards
+                    .attackers_to(target, occupied ^ square_bit(attacker_index))
+                    & occupied
+                    & bitboards.colours[colour_index(colour.opponent())];
+                if defenders != 0 {
+                    break; // king can't take a defended piece
+                }
+            }
+
+            if depth + 1 == MAX_EXCHANGE {
+                break;
+            }
+            depth += 1;
+
+            gains[depth] = exchange_value(piece_on_target) - gains[depth - 1]; // score if the exchange stopped here
+            occupied ^= square_bit(attacker_index); // reveals anything lined up behind the attacker
+            piece_on_target = attacker_type;
+        }
+
+        // each side stops recapturing when it would lose out by carrying on
+        while depth > 0 {
+            gains[depth - 1] = -(-gains[depth - 1]).max(gains[depth]);
+            depth -= 1;
+        }
+
+        gains[0]
+    }
+
+    pub fn is_losing_capture(&self, piece_move: Move) -> bool {
+        // returns true if the move takes a piece but loses more material than it wins
+        self.captured_piece(piece_move) != PieceType::None && self.static_exchange(piece_move) < 0
+    }
+
+    pub fn mvv_lva_score(&self, piece_move: Move) -> i32 {
+        // returns a score for ordering captures, most valuable victim first then least valuable attacker (0 for other moves)
+        let victim = self.captured_piece(piece_move);
+        if victim == PieceType::None {
+            return 0;
+        }
+
+        let attacker = self.board[piece_move.start as usize].piece_type;
+        exchange_value(victim) * 10 - exchange_value(attacker) / 100 + 1
+    }
+
+    pub fn order_mvv_lva(&self, moves: &mut [Move]) {
+        // sorts moves so captures come first in most valuable victim / least valuable attacker order
+        moves.sort_by_cached_key(|&piece_move| -self.mvv_lva_score(piece_move));
+    }
+}
@@ -0,0 +1,693 @@
+use std::{error, fmt};
+
+use crate::bitboard::{colour_index, square_bit, type_index, Bitboard, Bitboards};
+use crate::crazyhouse::Pockets;
+use crate::piece::{Piece, PieceColour, PieceType};
+use crate::position::{square_index, square_name, Position};
+use crate::variant::{Variant, CHECKS_TO_WIN};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FenError {
+    // reasons a fen string can be rejected (position is the index of the offending character)
+    MissingFields { found: usize },
+    BadRankCount { found: usize, position: usize },
+    BadRankLength { rank: usize, position: usize }, // rank as numbered on the board (8 to 1)
+    UnknownPiece { character: char, position: usize },
+    BadSideToMove { position: usize },
+    BadCastling { character: char, position: usize },
+    BadEnPassant { position: usize },
+    BadCounters { position: usize },
+    BadChecks { position: usize },
+    BadPocket { position: usize },
+    BadKingCount { colour: PieceColour, found: usize }, // each side needs exactly one king (any number in antichess)
+    PawnOnBackRank { position: usize },
+    CastlingWithoutPieces { character: char, position: usize }, // the king or rook for a castling right isn't on its square
+    OpponentInCheck, // the side that just moved left its own king attacked
+}
+
+impl fmt::Display for FenError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            FenError::MissingFields { found } => {
+                write!(f, "expected at least 4 fields but found {}", found)
+            }
+            FenError::BadRankCount { found, position } => write!(
+                f,
+                "expected 8 ranks but found {} (at character {})",
+                found, position
+            ),
+            FenError::BadRankLength { rank, position } => write!(
+                f,
+                "rank {} does not have 8 squares (at character {})",
+                rank, position
+            ),
+            FenError::UnknownPiece {
+                character,
+                position,
+            } => write!(
+                f,
+                "unknown piece '{}' (at character {})",
+                character, position
+            ),
+            FenError::BadSideToMove { position } => write!(
+                f,
+                "side to move must be 'w' or 'b' (at character {})",
+                position
+            ),
+            FenError::BadCastling {
+                character,
+                position,
+            } => write!(
+                f,
+                "unexpected castling right '{}' (at character {})",
+                character, position
+            ),
+            FenError::BadEnPassant { position } => write!(
+                f,
+                "en passant target must be '-' or the square behind a pawn that just double moved (at character {})",
+                position
+            ),
+            FenError::BadCounters { position } => write!(
+                f,
+                "move counters must be whole numbers (at character {})",
+                position
+            ),
+            FenError::BadChecks { position } => write!(
+                f,
+                "check counts must look like '3+3' (checks left) or '+0+0' (checks given) (at character {})",
+                position
+            ),
+            FenError::BadPocket { position } => write!(
+                f,
+                "pocket must be pieces other than kings inside '[' and ']' (e.g: [QNp]) (at character {})",
+                position
+            ),
+            FenError::BadKingCount { colour, found } => write!(
+                f,
+                "{} must have exactly one king but has {}",
+                format!("{:?}", colour).to_lowercase(),
+                found
+            ),
+            FenError::PawnOnBackRank { position } => write!(
+                f,
+                "pawns can't stand on the first or last rank (at character {})",
+                position
+            ),
+            FenError::CastlingWithoutPieces {
+                character,
+                position,
+            } => write!(
+                f,
+                "castling right '{}' needs the king and rook on their starting squares (at character {})",
+                character, position
+            ),
+            FenError::OpponentInCheck => {
+                write!(f, "the side not to move can't be in check")
+            }
+        }
+    }
+}
+
+impl error::Error for FenError {}
+
+fn split_fields(fen_string: &str) -> Vec<(usize, &str)> {
+    // splits a fen string on spaces, keeping the index each field starts at
+    let mut fields: Vec<(usize, &str)> = Vec::new();
+    let mut offset = 0;
+
+    for field in fen_string.split(' ') {
+        if !field.is_empty() {
+            fields.push((offset, field));
+        }
+        offset += field.len() + 1;
+    }
+
+    fields
+}
+
+fn decode_board(placement: &str, offset: usize) -> Result<(Vec<Piece>, Bitboard), FenError> {
+    // returns the board layout given by the piece placement field and the squares of promoted pieces (marked with a '~')
+    let mut board: Vec<Piece> = Vec::new();
+    let mut promoted: Bitboard = 0;
+    let mut rank_start = offset; // index the current rank starts at
+    let mut rank_count = 0;
+
+    for rank in placement.split('/') {
+        rank_count += 1;
+        if rank_count > 8 {
+            return Err(FenError::BadRankCount {
+                found: placement.split('/').count(),
+                position: rank_start,
+            });
+        }
+
+        let rank_number = 9 - rank_count; // rank as numbered on the board
+        let mut rank_length = 0;
+
+        for (char_index, file) in rank.char_indices() {
+            let position = rank_start + char_index;
+
+            if file == '~' {
+                // the piece before was a pawn (crazyhouse)
+                match rank[..char_index]
+                    .chars()
+                    .last()
+                    .and_then(Piece::from_fen_char)
+                {
+                    Some(_) => promoted |= square_bit(board.len() as i32 - 1),
+                    None => {
+                        return Err(FenError::UnknownPiece {
+                            character: file,
+                            position,
+                        })
+                    }
+                }
+                continue;
+            }
+
+            match file.to_digit(10) {
+                Some(empty_count) => {
+                    // empty spaces
+                    if empty_count == 0 {
+                        return Err(FenError::BadRankLength {
+                            rank: rank_number,
+                            position,
+                        });
+                    }
+                    for _ in 0..empty_count {
+                        board.push(Piece::EMPTY); // add empty spaces to board
+                    }
+                    rank_length += empty_count as usize;
+                }
+                None => match Piece::from_fen_char(file) {
+                    Some(piece) => {
+                        if piece.piece_type == PieceType::Pawn
+                            && (rank_number == 8 || rank_number == 1)
+                        {
+                            return Err(FenError::PawnOnBackRank { position });
+                        }
+                        board.push(piece); // add piece to board
+                        rank_length += 1;
+                    }
+                    None => {
+                        return Err(FenError::UnknownPiece {
+                            character: file,
+                            position,
+                        })
+                    } // not piece character in fen string (e.g: d)
+                },
+            }
+
+            if rank_length > 8 {
+                // rank overflows the board
+                return Err(FenError::BadRankLength {
+                    rank: rank_number,
+                    position,
+                });
+            }
+        }
+
+        if rank_length != 8 {
+            // rank is too short
+            return Err(FenError::BadRankLength {
+                rank: rank_number,
+                position: rank_start + rank.len(),
+            });
+        }
+
+        rank_start += rank.len() + 1;
+    }
+
+    if rank_count != 8 {
+        return Err(FenError::BadRankCount {
+            found: rank_count,
+            position: offset + placement.len(),
+        });
+    }
+
+    Ok((board, promoted))
+}
+
+fn decode_pockets(pocket_field: &str, offset: usize) -> Result<Pockets, FenError> {
+    // returns the pieces each side holds given by the pocket part of the placement field (e.g: [QNp], offset is the '[')
+    let bad_pocket = FenError::BadPocket { position: offset };
+    let pieces = pocket_field
+        .strip_prefix('[')
+        .and_then(|pocket| pocket.strip_suffix(']'))
+        .ok_or(bad_pocket)?;
+
+    let mut pockets: Pockets = [[0; 5]; 2];
+    for (char_index, pocket_char) in pieces.char_indices() {
+        match Piece::from_fen_char(pocket_char) {
+            Some(piece) if piece.piece_type != PieceType::King => {
+                pockets[colour_index(piece.piece_colour)][type_index(piece.piece_type)] += 1
+            }
+            _ => {
+                return Err(FenError::BadPocket {
+                    position: offset + 1 + char_index,
+                })
+            }
+        }
+    }
+
+    Ok(pockets)
+}
+
+struct Castling {
+    // what the castling field says
+    rights: (bool, bool, bool, bool),
+    rooks: (i32, i32, i32, i32), // squares the rooks castle from
+    chess960: bool, // rook files were named or the pieces don't start on the usual squares
+}
+
+fn back_rank_start(colour: PieceColour) -> i32 {
+    // returns the index of the a file square on a colour's back rank
+    match colour {
+        PieceColour::White => 56,
+        _ => 0,
+    }
+}
+
+fn has_piece(board: &[Piece], index: i32, piece_type: PieceType, colour: PieceColour) -> bool {
+    // returns true if the square holds a piece of the given type and colour
+    board[index as usize].piece_type == piece_type && board[index as usize].piece_colour == colour
+}
+
+fn outermost_rook(board: &[Piece], colour: PieceColour, king_side: bool) -> Option<i32> {
+    // returns the rook furthest from the king on one side of it along the back rank (None if there isn't one)
+    let back_rank_start = back_rank_start(colour);
+    let king_file =
+        (0..8).find(|&file| has_piece(board, back_rank_start + file, PieceType::King, colour))?;
+
+    let mut files: Vec<i32> = match king_side {
+        true => (king_file + 1..8).rev().collect(),
+        false => (0..king_file).collect(),
+    };
+    files.retain(|&file| has_piece(board, back_rank_start + file, PieceType::Rook, colour));
+
+    files.first().map(|&file| back_rank_start + file)
+}
+
+fn decode_castling_rights(
+    castling_field: &str,
+    offset: usize,
+    board: &[Piece],
+) -> Result<Castling, FenError> {
+    // returns castling rights given by the castling field (KQkq, or the files of the rooks as in Shredder-FEN and X-FEN)
+    let mut castling = Castling {
+        rights: (false, false, false, false),
+        rooks: (63, 56, 7, 0), // corners when the king is on the e file
+        chess960: false,
+    };
+
+    if castling_field == "-" {
+        // '-' = no castling possible
+        return Ok(castling);
+    }
+
+    for (char_index, castling_char) in castling_field.char_indices() {
+        let bad_castling = FenError::BadCastling {
+            character: castling_char,
+            position: offset + char_index,
+        };
+        let colour = match castling_char.is_ascii_uppercase() {
+            true => PieceColour::White,
+            false => PieceColour::Black,
+        };
+        let back_rank_start = back_rank_start(colour);
+
+        let (king_side, rook_index) = match castling_char {
+            'K' | 'k' => (
+                true,
+                outermost_rook(board, colour, true).unwrap_or(back_rank_start + 7),
+            ),
+            'Q' | 'q' => (
+                false,
+                outermost_rook(board, colour, false).unwrap_or(back_rank_start),
+            ),
+            'A'..='H' | 'a'..='h' => {
+                // file of the rook (Shredder-FEN), the side is whichever side of the king it's on
+                let file = castling_char.to_ascii_lowercase() as i32 - 'a' as i32;
+                let king_file = (0..8)
+                    .find(|&king_file| {
+                        has_piece(board, back_rank_start + king_file, PieceType::King, colour)
+                    })
+                    .ok_or(bad_castling)?;
+                if file == king_file {
+                    return Err(bad_castling);
+                }
+                castling.chess960 = true;
+                (file > king_file, back_rank_start + file)
+            }
+            _ => return Err(bad_castling),
+        };
+
+        let king_home =
+            (0..8).any(|file| has_piece(board, back_rank_start + file, PieceType::King, colour));
+        if !king_home || !has_piece(board, rook_index, PieceType::Rook, colour) {
+            return Err(FenError::CastlingWithoutPieces {
+                character: castling_char,
+                position: offset + char_index,
+            });
+        }
+
+        match (colour, king_side) {
+            (PieceColour::White, true) => {
+                (castling.rights.0, castling.rooks.0) = (true, rook_index)
+            }
+            (PieceColour::White, false) => {
+                (castling.rights.1, castling.rooks.1) = (true, rook_index)
+            }
+            (_, true) => (castling.rights.2, castling.rooks.2) = (true, rook_index),
+            (_, false) => (castling.rights.3, castling.rooks.3) = (true, rook_index),
+        }
+
+        let usual_squares = has_piece(board, back_rank_start + 4, PieceType::King, colour)
+            && rook_index % 8 == if king_side { 7 } else { 0 };
+        castling.chess960 |= !usual_squares; // KQkq for a chess960 start (X-FEN)
+    }
+
+    Ok(castling)
+}
+
+fn decode_en_passant(
+    en_passant_field: &str,
+    offset: usize,
+    colours_turn: PieceColour,
+    board: &[Piece],
+) -> Result<i32, FenError> {
+    // returns index of the en passant target square (-1 if there is none), it has to be behind a pawn that just double moved
+    if en_passant_field == "-" {
+        return Ok(-1);
+    }
+
+    let (target_rank, pushed_pawn_step, pushed_colour) = match colours_turn {
+        PieceColour::White => (2, 8, PieceColour::Black), // rank 6, black pawn on rank 5
+        _ => (5, -8, PieceColour::White),                 // rank 3, white pawn on rank 4
+    };
+
+    match square_index(en_passant_field) {
+        Some(index)
+            if index / 8 == target_rank
+                && has_piece(
+                    board,
+                    index + pushed_pawn_step,
+                    PieceType::Pawn,
+                    pushed_colour,
+                ) =>
+        {
+            Ok(index)
+        }
+        _ => Err(FenError::BadEnPassant { position: offset }),
+    }
+}
+
+fn decode_checks(checks_field: &str, offset: usize) -> Result<(u32, u32), FenError> {
+    // returns the checks given by white and black from a three-check field ("3+3" checks left, or "+0+0" checks given)
+    let bad_checks = FenError::BadChecks { position: offset };
+    let (counts, checks_left) = match checks_field.strip_prefix('+') {
+        Some(checks_given) => (checks_given, false),
+        None => (checks_field, true),
+    };
+
+    let (white_count, black_count) = counts.split_once('+').ok_or(bad_checks)?;
+    let white_count = white_count.parse::<u32>().map_err(|_| bad_checks)?;
+    let black_count = black_count.parse::<u32>().map_err(|_| bad_checks)?;
+
+    if white_count > CHECKS_TO_WIN || black_count > CHECKS_TO_WIN {
+        return Err(bad_checks);
+    }
+
+    match checks_left {
+        true => Ok((CHECKS_TO_WIN - white_count, CHECKS_TO_WIN - black_count)),
+        false => Ok((white_count, black_count)),
+    }
+}
+
+fn decode_counter(counter_field: &str, offset: usize) -> Result<u32, FenError> {
+    // returns value of a move counter field
+    counter_field
+        .parse::<u32>()
+        .map_err(|_| FenError::BadCounters { position: offset })
+}
+
+impl Position {
+    pub fn from_fen(fen_string: &str) -> Result<Position, FenError> {
+        // returns board state given by a fen string (played as three-check if it has check counts)
+        Position::from_fen_with_variant(fen_string, Variant::Standard)
+    }
+
+    pub fn from_fen_with_variant(
+        fen_string: &str,
+        mut variant: Variant,
+    ) -> Result<Position, FenError> {
+        // returns board state given by a fen string for a game of the given variant
+        let mut fields = split_fields(fen_string);
+
+        if fields.len() < 4 {
+            return Err(FenError::MissingFields {
+                found: fields.len(),
+            });
+        }
+
+        // -- PIECE PLACEMENT -- (the board layout, with the pockets after it in crazyhouse)
+
+        let (placement_offset, placement_field) = fields[0];
+        let (placement, pocket_field) = match placement_field.find('[') {
+            Some(pocket_start) => placement_field.split_at(pocket_start),
+            None => (placement_field, ""),
+        };
+
+        let (board, promoted) = decode_board(placement, placement_offset)?;
+
+        let mut pockets: Pockets = [[0; 5]; 2];
+        if !pocket_field.is_empty() {
+            pockets = decode_pockets(pocket_field, placement_offset + placement.len())?;
+            if variant == Variant::Standard {
+                variant = Variant::Crazyhouse;
+            }
+        }
+
+        // -- COLOURS TURN -- (which players turn it is)
+
+        let colours_turn = match fields[1].1 {
+            "w" => PieceColour::White, // white = 'w'
+            "b" => PieceColour::Black, // black = 'b'
+            _ => {
+                return Err(FenError::BadSideToMove {
+                    position: fields[1].0,
+                })
+            } // something went wrong
+        };
+
+        // -- CASTLING RIGHTS -- (players rights to castle on each side )
+
+        let castling = decode_castling_rights(fields[2].1, fields[2].0, &board)?;
+
+        // -- EN PASSANT TARGET -- (which square can be moved to by en passant)
+
+        let en_passant_move = decode_en_passant(fields[3].1, fields[3].0, colours_turn, &board)?;
+
+        // -- CHECK COUNTS -- (three-check only, either after the en passant target or at the end)
+
+        let mut checks_given = (0, 0);
+        let checks_field_index = fields
+            .iter()
+            .skip(4)
+            .position(|(_, field)| field.contains('+'));
+
+        if let Some(checks_field_index) = checks_field_index {
+            let (offset, checks_field) = fields.remove(checks_field_index + 4);
+            checks_given = decode_checks(checks_field, offset)?;
+            if variant == Variant::Standard {
+                variant = Variant::ThreeCheck;
+            }
+        }
+
+        // -- MOVE COUNTERS -- (optional, four field fens start counting from 0 and 1)
+
+        let halfmove_clock = match fields.get(4) {
+            Some(&(offset, counter_field)) => decode_counter(counter_field, offset)?,
+            None => 0,
+        };
+
+        let fullmove_number = match fields.get(5) {
+            Some(&(offset, counter_field)) => match decode_counter(counter_field, offset)? {
+                0 => return Err(FenError::BadCounters { position: offset }), // move numbers start at 1
+                fullmove_number => fullmove_number,
+            },
+            None => 1,
+        };
+
+        if fields.len() > 6 {
+            return Err(FenError::BadCounters {
+                position: fields[6].0,
+            });
+        }
+
+        let king_indexes = Position::find_kings(&board);
+
+        let bitboards = Bitboards::from_board(&board);
+        let mut position = Position {
+            board,
+            colours_turn,
+            castling_rights: castling.rights,
+            castling_rooks: castling.rooks,
+            chess960: castling.chess960,
+            en_passant_move,
+            king_indexes,
+            halfmove_clock,
+            fullmove_number,
+            hash: 0,
+            bitboards,
+            variant,
+            checks_given,
+            pockets,
+            promoted,
+        };
+        position.hash = position.compute_hash();
+
+        if position.variant != Variant::Antichess {
+            for colour in [PieceColour::White, PieceColour::Black] {
+                let found = position
+                    .board
+                    .iter()
+                    .filter(|piece| {
+                        piece.piece_type == PieceType::King && piece.piece_colour == colour
+                    })
+                    .count();
+                if found != 1 {
+                    return Err(FenError::BadKingCount { colour, found });
+                }
+            }
+        }
+
+        let mut waiting_side = position.clone(); // side that isn't to move
+        waiting_side.colours_turn = colours_turn.opponent();
+        if waiting_side.is_in_check() {
+            return Err(FenError::OpponentInCheck);
+        }
+
+        Ok(position)
+    }
+
+    pub fn to_fen(&self) -> String {
+        // returns fen string of current board
+        let mut fen_string = String::new();
+
+        // -- BOARD LAYOUT --
+
+        let mut board_layout_str: String = String::new();
+
+        let mut num_empty = 0; // number of empty spaces in a row
+
+        for rank in 0..8 {
+            for file in 0..8 {
+                match self.board[file + rank * 8].fen_char() {
+                    Some(piece_char) => {
+                        if num_empty != 0 {
+                            // add number of empty spaces to fen string when string of empty spaces end
+                            board_layout_str.push_str(&num_empty.to_string());
+                            num_empty = 0;
+                        }
+                        board_layout_str.push(piece_char);
+                        if self.promoted & square_bit((file + rank * 8) as i32) != 0 {
+                            board_layout_str.push('~'); // was a pawn
+                        }
+                    }
+                    None => num_empty += 1, // empty square
+                }
+            }
+
+            if num_empty != 0 {
+                // adding empty count if rank ends on empty spaces
+                board_layout_str.push_str(&num_empty.to_string());
+                num_empty = 0;
+            }
+
+            if rank != 7 {
+                // adding rank seperators
+                board_layout_str.push('/')
+            }
+        }
+
+        if self.variant == Variant::Crazyhouse {
+            // pieces each side holds
+            board_layout_str.push_str(&format!(
+                "[{}{}]",
+                self.pocket_string(PieceColour::White),
+                self.pocket_string(PieceColour::Black)
+            ));
+        }
+
+        fen_string.push_str(&(board_layout_str + " "));
+
+        // -- COLOURS TURN --
+
+        match self.colours_turn {
+            PieceColour::White => fen_string.push_str("w "),
+            PieceColour::Black => fen_string.push_str("b "),
+            _ => (),
+        }
+
+        // -- CASTLING RIGHTS --
+
+        let mut castling_rights_string = String::new();
+
+        for (has_right, colour, king_side, letter) in [
+            (self.castling_rights.0, PieceColour::White, true, 'K'), // white king side
+            (self.castling_rights.1, PieceColour::White, false, 'Q'), // white queen side
+            (self.castling_rights.2, PieceColour::Black, true, 'k'), // black king side
+            (self.castling_rights.3, PieceColour::Black, false, 'q'), // black queen side
+        ] {
+            if !has_right {
+                continue;
+            }
+
+            let rook_index = self.castling_rook(colour, king_side);
+            if !self.chess960 || outermost_rook(&self.board, colour, king_side) == Some(rook_index)
+            {
+                castling_rights_string.push(letter);
+            } else {
+                // another rook is further out, so name the file (X-FEN)
+                let file = (b'a' + (rook_index % 8) as u8) as char;
+                castling_rights_string.push(match colour {
+                    PieceColour::White => file.to_ascii_uppercase(),
+                    _ => file,
+                });
+            }
+        }
+
+        if castling_rights_string.is_empty() {
+            // if no castling is available
+            castling_rights_string.push('-');
+        }
+
+        fen_string.push_str(&(castling_rights_string + " "));
+
+        // -- EN PASSANT MOVE --
+
+        if self.en_passant_move == -1 {
+            fen_string.push_str("- ");
+        } else {
+            fen_string.push_str(&(square_name(self.en_passant_move) + " "));
+        }
+
+        // -- CHECK COUNTS --
+
+        if self.variant == Variant::ThreeCheck {
+            // checks each side has left to give
+            fen_string.push_str(&format!(
+                "{}+{} ",
+                CHECKS_TO_WIN.saturating_sub(self.checks_given.0),
+                CHECKS_TO_WIN.saturating_sub(self.checks_given.1)
+            ));
+        }
+
+        // -- MOVE COUNTERS --
+
+        fen_string.push_str(&format!("{} {}", self.halfmove_clock, self.fullmove_number));
+
+        fen_string
+    }
+}
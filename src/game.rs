@@ -0,0 +1,189 @@
+use crate::draw::{DrawReason, PositionHistory};
+use crate::hash_table::{HashStats, HashTable};
+use crate::pgn::GameResult;
+use crate::piece::PieceColour;
+use crate::position::{Move, Position, UndoInfo};
+use crate::status::GameStatus;
+
+const MOVE_CACHE_MEGABYTES: usize = 1; // default size of the legal move cache
+
+#[derive(Debug, Clone)]
+pub struct Game {
+    // a game being played (the current position plus every move that led to it)
+    pub start_position: Position,
+    pub position: Position,
+    played_moves: Vec<UndoInfo>,         // moves made so far, newest last
+    san_moves: Vec<String>,              // moves made so far in standard algebraic notation
+    undone_moves: Vec<Move>,             // moves taken back that can be redone, newest last
+    history: PositionHistory,            // positions reached (for repetitions)
+    claimed_draw: Option<DrawReason>,    // draw claimed or agreed by the players (ends the game)
+    resigned: Option<PieceColour>,       // colour of the player who resigned (ends the game)
+    recorded_result: Option<GameResult>, // result a loaded game was written down with (ends the game)
+    move_cache: HashTable<Vec<Move>>, // legal moves of positions already looked at (undo and redo revisit them)
+}
+
+impl Game {
+    pub fn new(start_position: Position) -> Game {
+        // starts a game from the given position
+        Game {
+            history: PositionHistory::new(&start_position),
+            position: start_position.clone(),
+            start_position,
+            played_moves: Vec::new(),
+            san_moves: Vec::new(),
+            undone_moves: Vec::new(),
+            claimed_draw: None,
+            resigned: None,
+            recorded_result: None,
+            move_cache: HashTable::with_megabytes(MOVE_CACHE_MEGABYTES),
+        }
+    }
+
+    pub fn make_move(&mut self, piece_move: Move) {
+        // plays a move (any moves waiting to be redone are forgotten)
+        self.undone_moves.clear();
+        self.push_move(piece_move);
+    }
+
+    fn push_move(&mut self, piece_move: Move) {
+        // plays a move and records it
+        self.san_moves.push(self.position.move_to_san(piece_move));
+        let undo_info = self.position.make_move(piece_move);
+        self.played_moves.push(undo_info);
+        self.history.push(&self.position);
+    }
+
+    pub fn undo(&mut self) -> bool {
+        // takes back the last move (returns false if there is nothing to undo)
+        match self.played_moves.pop() {
+            Some(undo_info) => {
+                self.position.unmake_move(undo_info);
+                self.claimed_draw = None;
+                self.resigned = None;
+                self.recorded_result = None;
+                self.history.pop();
+                self.san_moves.pop();
+                self.undone_moves.push(undo_info.piece_move);
+                true
+            }
+            None => false,
+        }
+    }
+
+    pub fn redo(&mut self) -> bool {
+        // plays the last move taken back again (returns false if there is nothing to redo)
+        match self.undone_moves.pop() {
+            Some(piece_move) => {
+                self.push_move(piece_move);
+                true
+            }
+            None => false,
+        }
+    }
+
+    pub fn legal_moves(&mut self) -> Vec<Move> {
+        // returns the legal moves in the current position (one per promotion piece), cached by position
+        self.position.legal_moves_cached(&mut self.move_cache)
+    }
+
+    pub fn set_move_cache_size(&mut self, megabytes: usize) {
+        // replaces the legal move cache with an empty one of the given size
+        self.move_cache = HashTable::with_megabytes(megabytes);
+    }
+
+    pub fn move_cache_stats(&self) -> HashStats {
+        // returns how often legal moves were found in the cache
+        self.move_cache.stats()
+    }
+
+    pub fn moves(&self) -> Vec<Move> {
+        // returns the moves played from the start position
+        self.played_moves
+            .iter()
+            .map(|undo_info| undo_info.piece_move)
+            .collect()
+    }
+
+    pub fn san_moves(&self) -> &[String] {
+        // returns the moves played in standard algebraic notation
+        &self.san_moves
+    }
+
+    pub fn move_list(&self) -> Vec<String> {
+        // returns the moves played as numbered lines (e.g: "1. e4 e5")
+        let mut lines: Vec<String> = Vec::new();
+        let mut move_number = self.start_position.fullmove_number;
+        let mut san_moves = self.san_moves.iter();
+
+        if self.start_position.colours_turn == PieceColour::Black {
+            // game started with black to move
+            if let Some(black_move) = san_moves.next() {
+                lines.push(format!("{}... {}", move_number, black_move));
+                move_number += 1;
+            }
+        }
+
+        while let Some(white_move) = san_moves.next() {
+            match san_moves.next() {
+                Some(black_move) => {
+                    lines.push(format!("{}. {} {}", move_number, white_move, black_move))
+                }
+                None => lines.push(format!("{}. {}", move_number, white_move)),
+            }
+            move_number += 1;
+        }
+
+        lines
+    }
+
+    pub fn history(&self) -> &PositionHistory {
+        // returns the positions reached so far
+        &self.history
+    }
+
+    pub fn status(&self) -> GameStatus {
+        // returns the game status including repetitions
+        self.history.status(&self.position)
+    }
+
+    pub fn claimable_draw(&self) -> Option<DrawReason> {
+        // returns a draw the player to move may claim (None if there isn't one)
+        self.history.claimable_draw(&self.position)
+    }
+
+    pub fn claim_draw(&mut self) -> Option<DrawReason> {
+        // claims a draw for the player to move (returns the reason, None if there isn't one to claim)
+        self.claimed_draw = self.claimable_draw();
+        self.claimed_draw
+    }
+
+    pub fn agree_draw(&mut self) {
+        // ends the game in a draw both players agreed to
+        self.claimed_draw = Some(DrawReason::Agreement);
+    }
+
+    pub fn claimed_draw(&self) -> Option<DrawReason> {
+        // returns the draw claimed or agreed to end the game (None if there wasn't one)
+        self.claimed_draw
+    }
+
+    pub fn resign(&mut self, colour: PieceColour) {
+        // ends the game with the given colour giving up
+        self.resigned = Some(colour);
+    }
+
+    pub fn resigned(&self) -> Option<PieceColour> {
+        // returns the colour that resigned (None if nobody has)
+        self.resigned
+    }
+
+    pub(crate) fn set_recorded_result(&mut self, result: GameResult) {
+        // ends the game with the result it was written down with
+        self.recorded_result = Some(result);
+    }
+
+    pub fn recorded_result(&self) -> Option<GameResult> {
+        // returns the result a loaded game finished with (None if it wasn't loaded finished, or a move has been taken back)
+        self.recorded_result
+    }
+}
@@ -0,0 +1,121 @@
+use std::mem;
+
+use crate::position::{Move, Position};
+
+#[derive(Debug, Clone)]
+struct Entry<T> {
+    // a stored result (key is the full position hash so different positions sharing a bucket aren't mixed up)
+    key: u64,
+    depth: u32,
+    value: T,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct HashStats {
+    // how the table has been used since it was made (or last cleared)
+    pub probes: u64,
+    pub hits: u64,
+    pub stores: u64,
+    pub overwrites: u64, // stores that replaced a different position
+}
+
+impl HashStats {
+    pub fn hit_rate(&self) -> f64 {
+        // returns the fraction of probes that found a stored result (0 if there were none)
+        match self.probes {
+            0 => 0.0,
+            probes => self.hits as f64 / probes as f64,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct HashTable<T: Clone> {
+    // fixed size table of results keyed by position hash, each bucket has a slot kept for the deepest result and one always replaced
+    buckets: Vec<[Option<Entry<T>>; 2]>,
+    stats: HashStats,
+}
+
+impl<T: Clone> HashTable<T> {
+    pub fn with_megabytes(megabytes: usize) -> HashTable<T> {
+        // returns an empty table using about the given amount of memory (heap data inside values isn't counted)
+        let bucket_size = mem::size_of::<[Option<Entry<T>>; 2]>();
+        let wanted_buckets = (megabytes * 1024 * 1024 / bucket_size).max(1);
+        let bucket_count = 1 << wanted_buckets.ilog2(); // power of two so the index is a mask of the hash
+
+        HashTable {
+            buckets: vec![[None, None]; bucket_count],
+            stats: HashStats::default(),
+        }
+    }
+
+    pub fn capacity(&self) -> usize {
+        // returns how many results the table can hold
+        self.buckets.len() * 2
+    }
+
+    fn bucket_index(&self, key: u64) -> usize {
+        // returns the bucket a key is stored in
+        (key as usize) & (self.buckets.len() - 1)
+    }
+
+    pub fn probe(&mut self, key: u64, depth: u32) -> Option<T> {
+        // returns the result stored for the position at the given depth (None if it isn't in the table)
+        self.stats.probes += 1;
+        let bucket = &self.buckets[self.bucket_index(key)];
+
+        let found = bucket
+            .iter()
+            .flatten()
+            .find(|entry| entry.key == key && entry.depth == depth)
+            .map(|entry| entry.value.clone());
+
+        if found.is_some() {
+            self.stats.hits += 1;
+        }
+        found
+    }
+
+    pub fn store(&mut self, key: u64, depth: u32, value: T) {
+        // saves a result (replaces the deep slot if this is at least as deep, otherwise the always replaced slot)
+        self.stats.stores += 1;
+        let bucket_index = self.bucket_index(key);
+        let bucket = &mut self.buckets[bucket_index];
+
+        let slot = match &bucket[0] {
+            Some(entry) if entry.depth > depth => 1, // even for the same position, keep the deeper result
+            _ => 0,
+        };
+
+        if bucket[slot].as_ref().is_some_and(|entry| entry.key != key) {
+            self.stats.overwrites += 1;
+        }
+        bucket[slot] = Some(Entry { key, depth, value });
+    }
+
+    pub fn stats(&self) -> HashStats {
+        // returns how well the table has been doing
+        self.stats
+    }
+
+    pub fn clear(&mut self) {
+        // forgets every stored result and resets the statistics
+        for bucket in self.buckets.iter_mut() {
+            *bucket = [None, None];
+        }
+        self.stats = HashStats::default();
+    }
+}
+
+impl Position {
+    pub fn legal_moves_cached(&self, move_cache: &mut HashTable<Vec<Move>>) -> Vec<Move> {
+        // returns the same as legal_moves_with_promotions, looking in the cache first
+        if let Some(legal_moves) = move_cache.probe(self.hash, 0) {
+            return legal_moves;
+        }
+
+        let legal_moves = self.legal_moves_with_promotions();
+        move_cache.store(self.hash, 0, legal_moves.clone());
+        legal_moves
+    }
+}
@@ -0,0 +1,39 @@
+// chess rules engine (board representation, fen parsing, move generation)
+
+pub mod atomic;
+pub mod bitboard;
+pub mod chess960;
+pub mod computer;
+pub mod crazyhouse;
+pub mod draw;
+pub mod evaluation;
+pub mod exchange;
+pub mod fen;
+pub mod game;
+pub mod hash_table;
+mod movegen;
+pub mod perft;
+pub mod pgn;
+pub mod piece;
+pub mod player;
+pub mod position;
+pub mod san;
+pub mod search;
+pub mod status;
+pub mod uci;
+pub mod variant;
+mod zobrist;
+
+pub use computer::ComputerPlayer;
+pub use draw::{DrawReason, PositionHistory};
+pub use fen::FenError;
+pub use game::Game;
+pub use hash_table::{HashStats, HashTable};
+pub use pgn::{GameResult, PgnError, PgnTags};
+pub use piece::{Piece, PieceColour, PieceType};
+pub use player::{Player, PlayerAction, RandomPlayer, ScriptedPlayer};
+pub use position::{square_index, square_name, Move, MoveFlag, Position, UndoInfo, START_FEN};
+pub use san::MoveParseError;
+pub use search::{SearchLimits, SearchResult, SearchStyle};
+pub use status::GameStatus;
+pub use variant::{Variant, VariantOutcome};
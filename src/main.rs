@@ -1,62 +1,18 @@
-use std::{cmp, fs, io, io::Write, process};
-
-#[derive(Debug, Copy, Clone, PartialEq, Eq)]
-enum PieceType {
-    // type of piece (None for empty space)
-    Pawn,
-    Rook,
-    Knight,
-    Bishop,
-    Queen,
-    King,
-    None,
-}
-
-#[derive(Debug, Copy, Clone, PartialEq, Eq)]
-enum PieceColour {
-    // colour of piece (None for empty space)
-    White,
-    Black,
-    None,
-}
-
-#[derive(Debug, Copy, Clone, PartialEq, Eq)]
-struct Piece {
-    // chess piece
-    piece_type: PieceType,
-    piece_colour: PieceColour,
-}
-
-#[derive(Debug, Copy, Clone, PartialEq, Eq)]
-enum MoveFlag {
-    // flags for special moves
-    EnPassant,
-    Castling,
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use std::{env, fs, io, io::Write, process};
 
-    Promotion, // general promotion flag
-
-    // promotion flags added later when type of promotion is chosen
-    RookPromo,
-    KnightPromo,
-    BishopPromo,
-    QueenPromo,
-    None,
-}
-
-#[derive(Debug, Copy, Clone)]
-struct Move {
-    start: i32,
-    end: i32,
-    flag: MoveFlag,
-}
+use chess::{
+    chess960::CHESS960_POSITIONS, computer::MAX_LEVEL, perft, square_index, uci, ComputerPlayer,
+    DrawReason, Game, GameStatus, HashTable, Move, MoveFlag, MoveParseError, PgnTags, Piece,
+    PieceColour, PieceType, Player, PlayerAction, Position, RandomPlayer, ScriptedPlayer,
+    SearchLimits, SearchStyle, Variant,
+};
 
 // ------- 	FEN STUFF -------
 
-fn input_fen() -> String {
-    let fen_string: String;
-
+fn input_fen() -> Position {
     loop {
-        println!("Enter the fen string you would like to use: (e.g. rnbqkbnr/pppppppp/8/8/4P3/8/PPPP1PPP/RNBQKBNR b KQkq e3)");
+        println!("Enter the fen string you would like to use: (e.g. rnbqkbnr/pppppppp/8/8/4P3/8/PPPP1PPP/RNBQKBNR b KQkq e3 0 1)");
         let mut input = String::new();
         io::stdin()
             .read_line(&mut input)
@@ -64,150 +20,135 @@ fn input_fen() -> String {
 
         println!("{}", input);
 
-        match decode_fen(&input.trim()).0.len() {
-            // if length of board layout is 0 fen string is invalid (see decode fen)
-            0 => println!("-- Not a valid fen string! --"),
-            _ => {
-                fen_string = input;
-                break;
-            }
+        match Position::from_fen(input.trim()) {
+            // say what is wrong if fen string is invalid (see Position::from_fen)
+            Err(fen_error) => println!("-- Not a valid fen string: {} --", fen_error),
+            Ok(position) => return position,
         }
     }
+}
+
+// ------- VARIANT STUFF -------
 
-    fen_string
+fn input_chess960() -> Position {
+    // returns the chess960 start position the player picks (or a random one)
+    let number = match menu_selection(vec!["Random Position", "Choose Position Number"]) {
+        1 => {
+            let nanos = SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|time| time.subsec_nanos())
+                .unwrap_or(0);
+            nanos % CHESS960_POSITIONS
+        }
+        _ => loop {
+            match number_input("Enter the position number (0 to 959, 518 is the normal start)") {
+                number if number < CHESS960_POSITIONS => break number,
+                _ => println!("-- Not a valid position number! --"),
+            }
+        },
+    };
+
+    println!("\nChess960 position {}", number);
+    Position::chess960(number).expect("\x1b[41m--INVALID CHESS960 NUMBER--\x1b[0m")
 }
 
-fn decode_fen(fen_string: &str) -> (Vec<Piece>, PieceColour, (bool, bool, bool, bool), i32) {
-    // returns board state given by a fen string (if fen string is invalid board layout returned will be empty vector)
-    let fen_parts: Vec<&str> = fen_string.split(" ").collect();
-    let mut return_tuple: (Vec<Piece>, PieceColour, (bool, bool, bool, bool), i32) = (
-        Vec::new(),
-        PieceColour::None,
-        (false, false, false, false),
-        -1,
-    ); // board, colours turn, en passant move, castling rights
-
-    // -- PIECE PLACEMENT -- (the board layout)
-
-    let ranks = fen_parts[0].split("/"); // first part of the fen string (piece placement)
-    let mut board: Vec<Piece> = Vec::new();
-
-    for rank in ranks {
-        for file in rank.chars() {
-            if file.is_numeric() {
-                // empty space
-                for _ in 0..file.to_digit(10).unwrap() {
-                    // add empty spaces to board
-                    board.push(Piece {
-                        piece_type: PieceType::None,
-                        piece_colour: PieceColour::None,
-                    });
-                }
-            } else {
-                // piece
-                let mut piece_type = PieceType::None;
-                let piece_colour;
-
-                match file.to_lowercase().to_string().as_str() {
-                    "p" => piece_type = PieceType::Pawn,   // pawn (p)
-                    "r" => piece_type = PieceType::Rook,   // rook (r)
-                    "n" => piece_type = PieceType::Knight, // knight (n)
-                    "b" => piece_type = PieceType::Bishop, // bishop (b)
-                    "q" => piece_type = PieceType::Queen,  // queen (q)
-                    "k" => piece_type = PieceType::King,   // king (k)
-                    _ => {
-                        println!("\x1b[41m-UNEXPECTED VALUE IN FEN STRING--\x1b[0m");
-                        return_tuple.0 = Vec::new();
-                    } // not piece character in fen string (e.g: d)
-                }
+fn choose_variant() -> Variant {
+    // returns the variant the player picks
+    let variants = &Variant::ALL[1..]; // standard chess is just 'Play'
+    let selection = menu_selection(variants.iter().map(|variant| variant.name()).collect());
 
-                match file.is_uppercase() {
-                    true => piece_colour = PieceColour::White, // white (uppercase)
-                    false => piece_colour = PieceColour::Black, // black (lowercase)
-                }
+    variants[selection as usize - 1]
+}
 
-                board.push(Piece {
-                    piece_type,
-                    piece_colour,
-                }); // add piece to board
-            }
-        }
-    }
-    if board.len() == 64 {
-        // checking length of board layout (if not 64 then fen string is invalid length)
-        return_tuple.0 = board;
-    } else {
-        println!("\x1b[41m--UNEXPECTED LENGTH FEN STRING--\x1b[0m");
-        return_tuple.0 = Vec::new();
-    }
+// ------- PGN STUFF -------
 
-    // -- COLOURS TURN -- (which players turn it is)
+fn input_pgn(piece_arts: &[[String; 9]]) -> Option<Game> {
+    // returns the game in a pgn file ready to play on from (None to go back to the start menu)
+    let mut game = loop {
+        let file_path = text_input(
+            "Enter the pgn file you would like to load (enter 'quit' to return to the start menu)",
+        );
 
-    match fen_parts[1] {
-        "w" => return_tuple.1 = PieceColour::White, // white = 'w'
-        "b" => return_tuple.1 = PieceColour::Black, // black = 'b'
-        _ => {
-            println!("\x1b[41m--UNEXPECTED VALUE IN FEN STRING--\x1b[0m");
-            return_tuple.0 = Vec::new();
-        } // something went wrong
+        if file_path == "quit" {
+            return None;
+        }
+
+        match fs::read_to_string(&file_path) {
+            Err(read_error) => println!("-- Could not read the file: {} --", read_error),
+            Ok(pgn) => match Game::from_pgn(&pgn) {
+                // say what is wrong if the pgn is invalid (see Game::from_pgn)
+                Err(pgn_error) => println!("-- Not a valid pgn: {} --", pgn_error),
+                Ok((game, tags)) => {
+                    println!(
+                        "\n{} vs {} ({}, {}) {} moves\n",
+                        tags.white,
+                        tags.black,
+                        tags.event,
+                        tags.date,
+                        game.moves().len()
+                    );
+                    if let Some(result) = game.recorded_result() {
+                        println!(
+                            "-- This game is already over ({}), step back through it to play on --",
+                            result.token()
+                        );
+                    }
+                    break game;
+                }
+            },
+        }
+    };
+
+    if menu_selection(vec!["Play From The Last Move", "Step Through The Game"]) == 2 {
+        step_through_game(&mut game, piece_arts);
     }
 
-    // -- CASTLING RIGHTS -- (players rights to castle on each side )
+    Some(game)
+}
 
-    let castling_rights_string = fen_parts[2];
+fn step_through_game(game: &mut Game, piece_arts: &[[String; 9]]) {
+    // shows the game one move at a time from the start (undone moves are replayed with redo)
+    while game.undo() {} // back to the start position
 
-    for castling_char in castling_rights_string.chars() {
-        match castling_char {
-            'K' => return_tuple.2 .0 = true, // white king side
-            'Q' => return_tuple.2 .1 = true, // white queen side
-            'k' => return_tuple.2 .2 = true, // black king side
-            'q' => return_tuple.2 .3 = true, // black queen side
-            '-' => return_tuple.2 = (false, false, false, false), // '-' = no castling possible
-            _ => {
-                println!("\x1b[41m--UNEXPECTED VALUE IN FEN STRING--\x1b[0m");
-                return_tuple.0 = Vec::new();
+    loop {
+        print_board(&game.position.board, &[], piece_arts, &move_list_text(game));
+
+        match menu_selection(vec!["Next Move", "Previous Move", "Play From Here"]) {
+            1 => {
+                if !game.redo() {
+                    println!("-- No more moves in the game! --");
+                }
+            }
+            2 => {
+                if !game.undo() {
+                    println!("-- Already at the start of the game! --");
+                }
             }
+            _ => return, // play on from the position shown
         }
     }
+}
 
-    // -- EN PASSANT TARGET -- (which square can be moved to by en passant)
-
-    let en_passant_target_algebraic = fen_parts[3]; // algebraic notaion of target square
-    let mut en_passant_target = -1; // index of target square
-
-    if en_passant_target_algebraic != "-" {
-        let number_part = en_passant_target_algebraic
-            .chars()
-            .nth(1)
-            .expect("\x1b[41m--UNEXPECTED VALUE IN FEN STRING--\x1b[0m")
-            .to_digit(10)
-            .unwrap();
-        let letter_part = en_passant_target_algebraic
-            .chars()
-            .nth(0)
-            .expect("\x1b[41m--UNEXPECTED VALUE IN FEN STRING--\x1b[0m");
-
-        match letter_part.to_lowercase().to_string().as_str() {
-            // changes letter part of algebraic notation into an int
-            "a" => en_passant_target += 0,
-            "b" => en_passant_target += 1,
-            "c" => en_passant_target += 2,
-            "d" => en_passant_target += 3,
-            "e" => en_passant_target += 4,
-            "f" => en_passant_target += 5,
-            "g" => en_passant_target += 6,
-            "h" => en_passant_target += 7,
-            _ => {
-                println!("\x1b[41m--UNEXPECTED VALUE IN FEN STRING--\x1b[0m");
-                return_tuple.0 = Vec::new();
-            } // unkown value (letter after 'h')
+fn save_pgn(game: &Game) {
+    // writes the game to a pgn file named by the player
+    let mut tags = PgnTags::new();
+
+    for (name, tag) in [("White", &mut tags.white), ("Black", &mut tags.black)] {
+        let player_name = text_input(&format!(
+            "Enter the name of the {} player (leave blank if unknown)",
+            name.to_lowercase()
+        ));
+        if !player_name.is_empty() {
+            *tag = player_name;
         }
-        en_passant_target += (8 - number_part as i32) * 8 + 1; // working out the index value ((8-number part) * 8 + 1) + letter part
     }
 
-    return_tuple.3 = en_passant_target;
+    let file_path = text_input("Enter the file to save the game to (e.g. game.pgn)");
 
-    return return_tuple;
+    match fs::write(&file_path, game.to_pgn(&tags)) {
+        Ok(()) => println!("\nGame saved to '{}'\n", file_path),
+        Err(write_error) => println!("-- Could not save the game: {} --", write_error),
+    }
 }
 
 fn load_board_art(file_path: &str) -> Vec<[String; 9]> {
@@ -232,170 +173,17 @@ fn load_board_art(file_path: &str) -> Vec<[String; 9]> {
     piece_arts
 }
 
-fn encode_into_fen(
-    board: &Vec<Piece>,
-    colours_turn: PieceColour,
-    castling_rights: (bool, bool, bool, bool),
-    en_passant_move: i32,
-) -> String {
-    // returns fen string of current board
-    let mut fen_string = String::new();
-
-    // -- BOARD LAYOUT --
-
-    let mut board_layout_str: String = String::new();
-
-    let mut empty_flag = false; // flag for number of empty spaces in a row
-    let mut num_empty = 0; // number of empty spaces in a row
-
-    for rank in 0..8 {
-        for file in 0..8 {
-            let piece = board[file + rank * 8];
-
-            if empty_flag == true && piece.piece_type != PieceType::None {
-                // add number of empty spaces to fen string when string of empty spaces end
-                board_layout_str.push_str(&num_empty.to_string());
-                empty_flag = false;
-                num_empty = 0;
-            }
-
-            match piece.piece_type {
-                PieceType::Pawn => {
-                    // pawn
-                    match piece.piece_colour {
-                        PieceColour::White => board_layout_str.push_str("P"), // white
-                        PieceColour::Black => board_layout_str.push_str("p"), // black
-                        _ => (),
-                    }
-                }
-                PieceType::Rook => {
-                    // rook
-                    match piece.piece_colour {
-                        PieceColour::White => board_layout_str.push_str("R"), // white
-                        PieceColour::Black => board_layout_str.push_str("r"), // black
-                        _ => (),
-                    }
-                }
-                PieceType::Knight => {
-                    // knight
-                    match piece.piece_colour {
-                        PieceColour::White => board_layout_str.push_str("N"), // white
-                        PieceColour::Black => board_layout_str.push_str("n"), // black
-                        _ => (),
-                    }
-                }
-                PieceType::Bishop => {
-                    // bishop
-                    match piece.piece_colour {
-                        PieceColour::White => board_layout_str.push_str("B"), // white
-                        PieceColour::Black => board_layout_str.push_str("b"), // black
-                        _ => (),
-                    }
-                }
-                PieceType::Queen => {
-                    // queen
-                    match piece.piece_colour {
-                        PieceColour::White => board_layout_str.push_str("Q"), // white
-                        PieceColour::Black => board_layout_str.push_str("q"), // black
-                        _ => (),
-                    }
-                }
-                PieceType::King => {
-                    // king
-                    match piece.piece_colour {
-                        PieceColour::White => board_layout_str.push_str("K"), // white
-                        PieceColour::Black => board_layout_str.push_str("k"), // black
-                        _ => (),
-                    }
-                }
-                PieceType::None => {
-                    // empty square
-                    empty_flag = true;
-                    num_empty += 1;
-                }
-            }
-        }
-
-        if num_empty != 0 {
-            // adding empty count if whole rank is empty (wont add at top if whole row is empty)
-            board_layout_str.push_str(&num_empty.to_string());
-            num_empty = 0;
-            empty_flag = false;
-        }
-
-        if rank != 7 {
-            // adding rank seperators
-            board_layout_str.push_str("/")
-        }
-    }
-
-    fen_string.push_str(&(board_layout_str + " "));
-
-    // -- COLOURS TURN --
-
-    match colours_turn {
-        PieceColour::White => fen_string.push_str("w "),
-        PieceColour::Black => fen_string.push_str("b "),
-        _ => (),
-    }
-
-    // -- CASTLING RIGHTS --
-
-    let mut castling_rights_string = String::new();
-
-    if castling_rights.0 == true {
-        castling_rights_string.push_str("K"); // white king side
-    }
-    if castling_rights.1 == true {
-        castling_rights_string.push_str("Q"); // white queen side
-    }
-    if castling_rights.2 == true {
-        castling_rights_string.push_str("k"); // black king side
-    }
-    if castling_rights.3 == true {
-        castling_rights_string.push_str("q"); // black queen side
-    }
-
-    if castling_rights_string.len() == 0 {
-        // if no castling is available
-        castling_rights_string.push_str("-");
-    }
-
-    fen_string.push_str(&(castling_rights_string + " "));
-
-    // -- EN PASSANT MOVE --
-
-    if en_passant_move == -1 {
-        fen_string.push_str("- ");
-    } else {
-        let letter_part = match en_passant_move % 8 {
-            // generate letter part of algebraic notation of en passant target
-            0 => "a",
-            1 => "b",
-            2 => "c",
-            3 => "d",
-            4 => "e",
-            5 => "f",
-            6 => "g",
-            7 => "h",
-            _ => "-",
-        };
-
-        let number_part = (8 - (en_passant_move / 8)).to_string(); // number part of algebraic notation of en passant target
-
-        let algebraic_notation_input = format!("{}{} ", letter_part, number_part);
-
-        fen_string.push_str(&algebraic_notation_input);
-    }
-
-    fen_string
-}
-
 // ------- BOARD PRINTING -------
 
-fn print_board(board: &Vec<Piece>, piece_moves: &Vec<Move>, piece_art: &Vec<[String; 9]>) {
-    // outputs the current board very fancily
+fn print_board(
+    board: &[Piece],
+    piece_moves: &[Move],
+    piece_art: &[[String; 9]],
+    side_text: &[String],
+) {
+    // outputs the current board very fancily (with side text such as the move list to the right of it)
     let mut lines: Vec<String> = vec![String::new(); 9];
+    let mut side_lines = side_text.iter();
 
     for rank in 0..=8 {
         let mut art_index: i32;
@@ -457,17 +245,15 @@ fn print_board(board: &Vec<Piece>, piece_moves: &Vec<Move>, piece_art: &Vec<[Str
                 for i in 0..=8 {
                     let mut line = piece_art[art_index as usize][i].clone();
 
-                    match (rank + 1) % 2 == (file + 1) % 2 {
-                        true => line = line.replace(" ", "#"), // Replace ' ' with '#' if White Square
-                        false => (),                           // Dont change if Black Square
+                    if (rank + 1) % 2 == (file + 1) % 2 {
+                        line = line.replace(' ', "#"); // Replace ' ' with '#' if White Square (Dont change if Black Square)
                     }
 
-                    match board[index].piece_colour {
-                        PieceColour::Black => line = line.replace("■", "\x1b[30m■\x1b[0m"), // Replace white square with black square if black piece
-                        _ => (), // Dont change if White Piece/Empty space
+                    if board[index].piece_colour == PieceColour::Black {
+                        line = line.replace('■', "\x1b[30m■\x1b[0m"); // Replace white square with black square if black piece (Dont change if White Piece/Empty space)
                     }
 
-                    for piece_move in piece_moves.into_iter() {
+                    for piece_move in piece_moves.iter() {
                         if index as i32 == piece_move.end {
                             if board[index].piece_type != PieceType::None {
                                 line = line.replace("#", "\x1b[32m@\x1b[0m"); // Highlight green if piece
@@ -485,436 +271,174 @@ fn print_board(board: &Vec<Piece>, piece_moves: &Vec<Move>, piece_art: &Vec<[Str
         }
 
         for line in lines {
-            println!("{}", line); // Output lines
+            match side_lines.next() {
+                Some(side_line) => println!("{}    {}", line, side_line), // Output lines with side text
+                None => println!("{}", line),                             // Output lines
+            }
         }
         lines = vec![String::new(); 9];
     }
 }
 
-// ------- PIECE MOVE GENERATION -------
-
-fn dist_to_edge(index: i32) -> [i32; 8] {
-    // finds distance to edge in each direction
-    let file = index % 8;
-    let rank = index / 8;
-
-    let south = 7 - rank; // down
-    let north = rank; // up
-    let east = 7 - file; // right
-    let west = file; // left
+// ------- PLAYERS -------
 
-    let south_east = cmp::min(south, east); // down right
-    let north_west = cmp::min(north, west); // up left
-    let south_west = cmp::min(south, west); // down left
-    let north_east = cmp::min(north, east); // up right
-
-    [
-        south, north, east, west, south_east, north_west, south_west, north_east,
-    ]
+struct HumanPlayer {
+    // a person choosing moves at the terminal
+    piece_arts: Vec<[String; 9]>,
 }
 
-fn legal_move_gen(
-    board: &mut Vec<Piece>,
-    index: i32,
-    en_passant_move: i32,
-    king_indexes: (i32, i32),
-    castling_rights: (bool, bool, bool, bool),
-) -> Vec<Move> {
-    // removes any piece moves that result in check
-    let sudo_legal_moves =
-        sudo_legal_move_gen(board, index, en_passant_move, castling_rights, true); // all possible moves that can be made by the piece
-    let mut legal_moves: Vec<Move> = Vec::new();
-
-    for sudo_move in sudo_legal_moves {
-        let (board_after_move, new_en_passant_move, _, _) = make_move(
-            &mut board.clone(), /*need the .clone() there to stop it from editing the actual board*/
-            sudo_move,
-            en_passant_move,
-            king_indexes,
-            castling_rights,
-        );
-        // sudo make move on board
-        let opponent_colour: PieceColour; // colour off opponents piece
-        let mut king_index: i32; // index of current players king
-
-        match board[index as usize].piece_colour {
-            // set the opponent colour and king index
-            PieceColour::White => {
-                opponent_colour = PieceColour::Black;
-                king_index = king_indexes.0
-            }
-            PieceColour::Black => {
-                opponent_colour = PieceColour::White;
-                king_index = king_indexes.1
-            }
-            _ => {
-                println!("\x1b[41m--UNEXPECTED PIECE COLOUR WHEN GENERATING MOVES--\x1b[0m");
-                return Vec::new();
-            } // return error if currently checked piece is blank space
-        }
+impl Player for HumanPlayer {
+    fn name(&self) -> String {
+        String::from("Human")
+    }
 
-        if board[index as usize].piece_type == PieceType::King {
-            // if the piece moving is the king move the king index with the move being checked  (stops king blocking check on itself)
-            king_index = sudo_move.end;
-        }
+    fn choose_action(&mut self, game: &Game, _legal_moves: &[Move]) -> PlayerAction {
+        selection_iteration(game, &self.piece_arts)
+    }
 
-        let opponent_responses = side_move_gen(
-            &board_after_move,
-            new_en_passant_move,
-            opponent_colour,
-            castling_rights,
-        ); // possible moves the opponent can make (check not respected)
-
-        // if any of the opponent responses results in king being taken then the move is not legal
-        let mut illigal_flag = false;
-        for opponent_move in opponent_responses {
-            if opponent_move.end == king_index {
-                illigal_flag = true;
-            }
-        }
+    fn accepts_draw(&mut self, game: &Game) -> bool {
+        println!("{:?} offers a draw", game.position.colours_turn.opponent());
+        menu_selection(vec!["Decline Draw", "Accept Draw"]) == 2
+    }
 
-        if illigal_flag == false {
-            // add the move to the legal moves if no responces result in the king being taken
-            legal_moves.push(sudo_move);
-        }
+    fn claims_draw(&mut self, game: &Game, draw_reason: DrawReason) -> bool {
+        println!(
+            "{:?} can claim a draw by {}",
+            game.position.colours_turn,
+            draw_reason_name(draw_reason).to_lowercase()
+        );
+        menu_selection(vec!["Keep Playing", "Claim Draw"]) == 2
     }
 
-    legal_moves
+    fn is_human(&self) -> bool {
+        true
+    }
 }
 
-fn sudo_legal_move_gen(
-    board: &Vec<Piece>,
-    index: i32,
-    en_passant_move: i32,
-    castling_rights: (bool, bool, bool, bool),
-    check_castling: bool,
-) -> Vec<Move> {
-    // generates moves without respect to check
-    let mut piece_moves: Vec<Move> = Vec::new();
-    let piece = board[index as usize];
-
-    match piece.piece_type {
-        PieceType::Rook | PieceType::Bishop | PieceType::Queen => {
-            // Rook Bishop Queen
-            let directions = [8, -8, 1, -1, 9, -9, 7, -7]; // south north east west south-east north-west south-west north-east
-            let edge_dist = dist_to_edge(index); // Dist to edges in each direction
-
-            //Cutting off directions piece cant move in
-            let start_dir = if piece.piece_type == PieceType::Bishop {
-                4
-            } else {
-                0
-            };
-            let end_dir = if piece.piece_type == PieceType::Rook {
-                4
-            } else {
-                8
-            };
-
-            for dir_index in start_dir..end_dir {
-                for dir_offset in 1..edge_dist[dir_index] + 1 {
-                    let dir = directions[dir_index];
-
-                    let new_index = index + (dir * dir_offset);
-
-                    if board[new_index as usize].piece_colour == piece.piece_colour {
-                        break;
-                    } // target piece same colour
-
-                    piece_moves.push(Move {
-                        start: index,
-                        end: new_index,
-                        flag: MoveFlag::None,
-                    });
+fn choose_player(colour: PieceColour, piece_arts: &[[String; 9]]) -> Box<dyn Player> {
+    // returns who (or what) the player picks to play the given colour
+    loop {
+        println!("\nWho is playing {:?}?", colour);
 
-                    if board[new_index as usize].piece_colour != PieceColour::None {
-                        break;
-                    } // target piece enemy colour
-                }
-            }
-        }
-        PieceType::Knight => {
-            let moves = [
-                (-2, -1),
-                (-1, -2),
-                (1, -2),
-                (2, -1),
-                (2, 1),
-                (1, 2),
-                (-1, 2),
-                (-2, 1),
-            ]; // Moves possible by knight
-
-            for knight_move in moves {
-                let new_file = (index % 8) + knight_move.0;
-                let new_rank = (index / 8) + knight_move.1;
-
-                if 0 > new_file || 7 < new_file || 0 > new_rank || 7 < new_rank {
-                    continue;
-                } // Removing moves that go off the board
-
-                if board[(new_file + new_rank * 8) as usize].piece_colour == piece.piece_colour {
-                    continue;
-                } // Removing moves that are targeting pieces of the same colour
-
-                piece_moves.push(Move {
-                    start: index,
-                    end: new_file + new_rank * 8,
-                    flag: MoveFlag::None,
+        match menu_selection(vec!["Human", "Computer", "Random Mover", "Scripted Moves"]) {
+            1 => {
+                return Box::new(HumanPlayer {
+                    piece_arts: piece_arts.to_vec(),
                 })
             }
-        }
-        PieceType::Pawn => {
-            let mut dir = -1; // direction of travel
-            let mut start_rank = 6; // rank on which the pawn starts
-            let mut promotion_rank = 0;
-            let mut en_passant_rank = 3; // rank on which the pawn can perform en passant
-            if piece.piece_colour == PieceColour::Black {
-                dir = 1;
-                start_rank = 1;
-                promotion_rank = 7;
-                en_passant_rank = 4;
-            } // black pawn values
-
-            if board[(index + (8 * dir)) as usize].piece_colour == PieceColour::None {
-                // single move forward
-                if (index + (8 * dir)) / 8 != promotion_rank {
-                    piece_moves.push(Move {
-                        start: index,
-                        end: index + (8 * dir),
-                        flag: MoveFlag::None,
-                    }); // not last rank
-                } else {
-                    piece_moves.push(Move {
-                        start: index,
-                        end: index + (8 * dir),
-                        flag: MoveFlag::Promotion,
-                    }); // last rank so promotion
-                }
-
-                if index + (16 * dir) >= 0 && index + (16 * dir) <= 63 {
-                    if board[(index + (16 * dir)) as usize].piece_colour == PieceColour::None
-                        && index / 8 == start_rank
-                    {
-                        // double move forward when on starting rank
-                        piece_moves.push(Move {
-                            start: index,
-                            end: index + (16 * dir),
-                            flag: MoveFlag::None,
-                        });
-                    }
-                }
-            }
-
-            for side in [-1i32, 1] {
-                if 0 > (index % 8) + side || 7 < (index % 8) + side {
-                    continue;
-                } // removing moves that go off the board
-
-                if board[(index + side + (8 * dir)) as usize].piece_colour != piece.piece_colour
-                    && board[(index + side + (8 * dir)) as usize].piece_colour != PieceColour::None
-                {
-                    // taking diagonally on each side
-                    if (index + side + (8 * dir)) / 8 != promotion_rank {
-                        piece_moves.push(Move {
-                            start: index,
-                            end: index + side + (8 * dir),
-                            flag: MoveFlag::None,
-                        }); // not last rank
-                    } else {
-                        piece_moves.push(Move {
-                            start: index,
-                            end: index + side + (8 * dir),
-                            flag: MoveFlag::Promotion,
-                        }); // last rank so promotion
-                    }
-                }
-
-                if en_passant_move == index + side + (8 * dir)
-                    && board[(index + side) as usize].piece_colour != piece.piece_colour
-                    && index / 8 == en_passant_rank
-                {
-                    // en passant
-                    piece_moves.push(Move {
-                        start: index,
-                        end: index + side + (8 * dir),
-                        flag: MoveFlag::EnPassant,
-                    })
-                }
-            }
-        }
-        PieceType::King => {
-            for x_change in -1..=1 {
-                for y_change in -1..=1 {
-                    if x_change != 0 || y_change != 0 {
-                        let new_file = (index % 8) + x_change;
-                        let new_rank = (index / 8) + y_change;
-
-                        if 0 > new_file || 7 < new_file || 0 > new_rank || 7 < new_rank {
-                            continue;
-                        } // removing moves that go off the board
-
-                        if board[(new_file + new_rank * 8) as usize].piece_colour
-                            != piece.piece_colour
-                        {
-                            // removing moves that are targeting a piece of the same colour
-                            piece_moves.push(Move {
-                                start: index,
-                                end: new_file + new_rank * 8,
-                                flag: MoveFlag::None,
-                            });
-                        }
-                    }
-                }
-            }
-
-            // castling stuff
-            if check_castling {
-                let opponent_moves = match piece.piece_colour {
-                    PieceColour::White => {
-                        side_move_gen(board, en_passant_move, PieceColour::Black, castling_rights)
-                    }
-                    PieceColour::Black => {
-                        side_move_gen(board, en_passant_move, PieceColour::White, castling_rights)
-                    }
-                    _ => Vec::new(),
-                };
-
-                'stuart: for dir in [-1i32, 1i32] {
-                    let mut blocking_indexs: Vec<i32> = Vec::new();
-                    match dir {
-                        -1 => {
-                            // queen side
-                            let can_castle = match piece.piece_colour {
-                                PieceColour::White => castling_rights.1,
-                                PieceColour::Black => castling_rights.3,
-                                _ => false,
-                            };
-
-                            blocking_indexs = vec![index - 1, index - 2, index - 3];
-
-                            if !can_castle {
-                                continue;
-                            }
-                        }
-                        1 => {
-                            // king side
-                            let can_castle = match piece.piece_colour {
-                                PieceColour::White => castling_rights.0,
-                                PieceColour::Black => castling_rights.2,
-                                _ => false,
-                            };
-
-                            blocking_indexs = vec![index + 1, index + 2];
-
-                            if !can_castle {
-                                continue;
-                            }
-                        }
-                        _ => (),
-                    }
-
-                    for index_to_check in blocking_indexs {
-                        if board[index_to_check as usize].piece_type != PieceType::None {
-                            continue 'stuart;
-                        }
-
-                        for enemy_move in &opponent_moves {
-                            if enemy_move.end == index_to_check {
-                                continue 'stuart;
-                            }
-                        }
-                    }
-
-                    piece_moves.push(Move {
-                        start: index,
-                        end: index + (2 * dir),
-                        flag: MoveFlag::Castling,
-                    });
+            2 => return Box::new(choose_computer()),
+            3 => return Box::new(RandomPlayer::new()),
+            _ => {
+                let file_path = text_input(
+                    "Enter the file of moves to play (e.g. moves.txt with 'e4 Nf3 Bc4')",
+                );
+                match ScriptedPlayer::from_file(&file_path) {
+                    Ok(scripted_player) => return Box::new(scripted_player),
+                    Err(read_error) => println!("-- Could not read the file: {} --", read_error),
                 }
             }
         }
-        _ => return Vec::new(),
     }
-    piece_moves
 }
 
-fn side_move_gen(
-    board: &Vec<Piece>,
-    en_passant_move: i32,
-    side_to_check: PieceColour,
-    castling_rights: (bool, bool, bool, bool),
-) -> Vec<Move> {
-    // generates all moves for a side
-    let mut moves: Vec<Move> = Vec::new();
-
-    for piece_index in 0..64 {
-        // loops through all pieces on the board
-
-        if board[piece_index].piece_colour == side_to_check {
-            // if piece is colour you want to check add piece moves to list
-            moves.append(&mut sudo_legal_move_gen(
-                board,
-                piece_index as i32,
-                en_passant_move,
-                castling_rights,
-                false,
-            ));
+fn choose_computer() -> ComputerPlayer {
+    // returns a computer player with the strength and style the player picks
+    let level = loop {
+        match number_input(&format!("Enter the computer's level (1 to {})", MAX_LEVEL)) {
+            level @ 1..=MAX_LEVEL => break level,
+            _ => println!("-- Not a valid level! --"),
         }
-    }
+    };
 
-    moves
+    println!("\nHow long should the computer think for each move?");
+    let mut limits = SearchLimits::default();
+    match menu_selection(vec!["Time Limit", "Node Limit"]) {
+        1 => {
+            let milliseconds = number_input("Enter the time in milliseconds").max(1);
+            limits.move_time = Some(Duration::from_millis(milliseconds as u64));
+        }
+        _ => {
+            let nodes = number_input("Enter the number of positions to search").max(1);
+            limits.nodes = Some(nodes as u64);
+        }
+    }
+
+    println!("\nHow should the computer play?");
+    let style = match menu_selection(vec!["Balanced", "Aggressive", "Cautious", "Custom"]) {
+        1 => SearchStyle::default(),
+        2 => SearchStyle {
+            contempt: 50, // plays on rather than draw
+            aggressiveness: 100,
+        },
+        3 => SearchStyle {
+            contempt: -25, // happy to draw
+            aggressiveness: -100,
+        },
+        _ => SearchStyle {
+            aggressiveness: integer_input(
+                "Enter the aggressiveness (-100 guards its king to 100 attacks yours)",
+            )
+            .clamp(-100, 100),
+            contempt: integer_input(
+                "Enter the contempt in centipawns (above 0 avoids draws, below 0 looks for them)",
+            ),
+        },
+    };
+
+    ComputerPlayer::new(level, limits, style)
 }
 
 // ------- PIECE MOVEMENT -------
 
-fn selection_iteration(
-    mut board: Vec<Piece>,
-    mut colours_turn: PieceColour,
-    en_passant_move: i32,
-    piece_arts: &Vec<[String; 9]>,
-    king_indexs: (i32, i32),
-    castling_rights: (bool, bool, bool, bool),
-) -> (
-    Vec<Piece>,
-    i32,
-    PieceColour,
-    (i32, i32),
-    (bool, bool, bool, bool),
-) {
-    // the main input loop of the game
+fn selection_iteration(game: &Game, piece_arts: &[[String; 9]]) -> PlayerAction {
+    // the main input loop of a human player's turn
     let mut piece_moves: Vec<Move>;
     let mut selected_move: Move;
 
     'outer: loop {
-        print_board(&board, &Vec::new(), piece_arts); // print current positions
-        piece_moves = select_piece(
-            &mut board,
-            colours_turn,
-            en_passant_move,
-            king_indexs,
-            castling_rights,
-        ); // select piece
-
-        if piece_moves.len() == 0 {
-            // if input was 'save'
-            println!(
-                "\nFen String:\n'{}'\n",
-                encode_into_fen(&board, colours_turn, castling_rights, en_passant_move)
-            );
-            loop {
-                let selection = menu_selection(vec!["Continue", "Quit"]);
+        print_board(&game.position.board, &[], piece_arts, &move_list_text(game)); // print current positions
 
-                match selection {
-                    1 => continue 'outer,  // continue game
-                    2 => process::exit(1), // exit program
-                    _ => (),
+        piece_moves = match select_piece(&game.position) {
+            // select piece (or a whole move typed in one go)
+            Ok(PieceSelection::Piece(piece_moves)) => piece_moves,
+            Ok(PieceSelection::Move(typed_move)) => {
+                selected_move = typed_move;
+                break;
+            }
+            Err(special_input) => {
+                match special_input.as_str() {
+                    "undo" => return PlayerAction::Undo, // take back a move
+                    "redo" => return PlayerAction::Redo, // play a move taken back again
+                    "resign" => return PlayerAction::Resign,
+                    "draw" => return PlayerAction::OfferDraw,
+                    _ => {
+                        // input was 'save'
+                        println!("\nFen String:\n'{}'\n", game.position.to_fen());
+                        loop {
+                            let selection = menu_selection(vec!["Continue", "Save PGN", "Quit"]);
+
+                            match selection {
+                                1 => break,            // continue game
+                                2 => save_pgn(game),   // write the game so far to a file
+                                3 => process::exit(1), // exit program
+                                _ => (),
+                            }
+                        }
+                    }
                 }
+                continue 'outer;
             }
-        }
+        };
 
-        print_board(&board, &piece_moves, piece_arts); // print piece moves
-        selected_move = select_move(&piece_moves); // select move
+        print_board(
+            &game.position.board,
+            &piece_moves,
+            piece_arts,
+            &move_list_text(game),
+        ); // print piece moves
+        print_losing_captures(&game.position, &piece_moves); // warn about captures that give material away
+        let king_promotion = game.position.variant == Variant::Antichess;
+        selected_move = select_move(&piece_moves, king_promotion); // select move
 
         if selected_move.start != selected_move.end {
             // exit loop if input was not 'quit'
@@ -922,89 +446,132 @@ fn selection_iteration(
         }
     }
 
-    match colours_turn {
-        // swap whos turn it is
-        PieceColour::White => colours_turn = PieceColour::Black,
-        PieceColour::Black => colours_turn = PieceColour::White,
-        _ => (),
+    PlayerAction::Move(selected_move)
+}
+
+fn print_losing_captures(position: &Position, piece_moves: &[Move]) {
+    // prints a warning for each of the moves that takes a piece but loses material once the recaptures are made
+    for &piece_move in piece_moves {
+        if position.is_losing_capture(piece_move) {
+            println!(
+                "\x1b[43;30m-- Careful: {} loses {} centipawns --\x1b[0m",
+                position.move_to_san(piece_move),
+                -position.static_exchange(piece_move)
+            );
+        }
     }
+}
 
-    let (new_board, en_passant_move, king_indexs, castling_rights) = make_move(
-        &mut board,
-        selected_move,
-        en_passant_move,
-        king_indexs,
-        castling_rights,
-    );
-    (
-        new_board,
-        en_passant_move,
-        colours_turn,
-        king_indexs,
-        castling_rights,
-    ) // make move and return new board
+fn move_list_text(game: &Game) -> Vec<String> {
+    // returns the evaluation and move list to show next to the board (only the latest moves if they don't all fit)
+    let white_score = match game.position.colours_turn {
+        PieceColour::White => game.position.evaluate(), // same score the computer searches with (variant rules included)
+        _ => -game.position.evaluate(),
+    };
+    let mut text = vec![format!(
+        "Evaluation: {:+.2} (for white)",
+        white_score as f64 / 100.0
+    )];
+    match game.position.variant {
+        Variant::Standard => (),
+        Variant::ThreeCheck => text.push(format!(
+            "Three-check: white has given {}, black has given {}",
+            game.position.checks_given.0, game.position.checks_given.1
+        )),
+        Variant::Crazyhouse => {
+            text.push(String::from("Crazyhouse pockets (drop with e.g: N@f3):"));
+            for colour in [PieceColour::White, PieceColour::Black] {
+                let pocket = game.position.pocket_string(colour).to_uppercase();
+                text.push(format!(
+                    "  {:?}: {}",
+                    colour,
+                    match pocket.is_empty() {
+                        true => String::from("-"),
+                        false => pocket,
+                    }
+                ));
+            }
+        }
+        variant => text.push(variant.name().to_string()),
+    }
+    text.push(String::new());
+    text.push(String::from("Moves:"));
+
+    let move_list = game.move_list();
+    let shown_lines = move_list.len().min(81 - text.len()); // board is 81 lines tall
+    text.extend_from_slice(&move_list[move_list.len() - shown_lines..]);
+    text
 }
 
-fn select_piece(
-    board: &mut Vec<Piece>,
-    colours_turn: PieceColour,
-    en_passant_move: i32,
-    king_indexs: (i32, i32),
-    castling_rights: (bool, bool, bool, bool),
-) -> Vec<Move> {
-    // returns moves of selected piece
-    let mut index: i32;
-    let mut piece_moves: Vec<Move>;
+enum PieceSelection {
+    // what the player entered when asked for a piece
+    Piece(Vec<Move>), // square of one of their pieces (with the moves it can make)
+    Move(Move),       // a whole move (e.g: Nf3, e2e4)
+}
 
+fn select_piece(position: &Position) -> Result<PieceSelection, String> {
+    // returns moves of selected piece or the move typed (or the special input entered instead)
     loop {
-        println!("{:?}'s turn!", colours_turn);
-        index = algebraic_notation_input("Enter the piece you would like to select, enter 'save' to get a fen string of the current board layout", false, true);
+        println!("{:?}'s turn!", position.colours_turn);
+        let input = text_input("Enter a move (e.g: Nf3, exd5, O-O, e7e8q, N@f3) or the piece you would like to select ('save' for a fen string of the board, 'undo' or 'redo' to take back or replay a move, 'draw' to offer a draw, 'resign' to give up)");
 
-        if index == -1 {
-            // if input was special ('save' in this count)
-            return Vec::new();
+        if ["save", "undo", "redo", "draw", "resign"].contains(&input.to_lowercase().as_str()) {
+            // input was special
+            return Err(input.to_lowercase());
         }
 
-        if board[index as usize].piece_colour == colours_turn {
-            // check if piece selected is current colours piece
-            piece_moves =
-                legal_move_gen(board, index, en_passant_move, king_indexs, castling_rights);
+        let selected_index = square_index(&input.to_lowercase()); // square of piece if input was a square
 
-            if piece_moves.len() > 0 {
-                // make sure the piece has atleast one move
-                break;
-            } else {
+        if let Some(index) = selected_index {
+            if position.board[index as usize].piece_colour == position.colours_turn {
+                // check if piece selected is current colours piece
+                let piece_moves = position.legal_moves_from(index);
+
+                if !piece_moves.is_empty() {
+                    // make sure the piece has atleast one move
+                    return Ok(PieceSelection::Piece(piece_moves));
+                }
                 println!("-- Piece has no moves to make! --");
+                continue;
             }
-        } else {
-            println!("-- Not Your Piece! --");
+        }
+
+        match position.parse_move(&input) {
+            Ok(typed_move) => return Ok(PieceSelection::Move(typed_move)),
+            Err(MoveParseError::IllegalMove)
+                if selected_index.is_some_and(|index| {
+                    position.board[index as usize].piece_colour != PieceColour::None
+                }) =>
+            {
+                println!("-- Not Your Piece! --")
+            }
+            Err(parse_error) => println!("-- Not a move you can make: {} --", parse_error),
         }
     }
-    piece_moves
 }
 
-fn select_move(piece_moves: &Vec<Move>) -> Move {
+fn select_move(piece_moves: &[Move], king_promotion: bool) -> Move {
     // returns selected move
     loop {
-        let index = algebraic_notation_input(
+        let index = match algebraic_notation_input(
             "Enter the move you would like to make (enter 'quit' to return to piece selection)",
-            true,
-            false,
-        );
-
-        if index == -1 {
-            // if input was special ('quit' in this case)
-            return Move {
-                start: -1,
-                end: -1,
-                flag: MoveFlag::None,
-            }; // go back to piece input
-        }
+            &["quit"],
+        ) {
+            Ok(index) => index,
+            Err(_) => {
+                // if input was special ('quit' in this case)
+                return Move {
+                    start: -1,
+                    end: -1,
+                    flag: MoveFlag::None,
+                }; // go back to piece input
+            }
+        };
 
-        for piece_move in piece_moves.into_iter() {
+        for piece_move in piece_moves.iter() {
             if piece_move.end == index {
                 if piece_move.flag == MoveFlag::Promotion {
-                    let promo_flag = promotion_type_input("Enter the type of piece this pawn should promote to (enter 'quit' to return to piece selection)", true);
+                    let promo_flag = promotion_type_input("Enter the type of piece this pawn should promote to (enter 'quit' to return to piece selection)", true, king_promotion);
 
                     if promo_flag == MoveFlag::None {
                         // go back to piece input
@@ -1029,266 +596,10 @@ fn select_move(piece_moves: &Vec<Move>) -> Move {
     }
 }
 
-fn make_move(
-    board: &mut Vec<Piece>,
-    piece_move: Move,
-    en_passant_move: i32,
-    mut king_indexs: (i32, i32),
-    mut castling_rights: (bool, bool, bool, bool),
-) -> (Vec<Piece>, i32, (i32, i32), (bool, bool, bool, bool)) {
-    // returns new board state for move made
-    let start_piece = board[piece_move.start as usize];
-    let capture_piece = board[piece_move.end as usize];
-    let mut new_en_passant = -1;
-
-    board[piece_move.end as usize] = start_piece; // move piece to new square
-    board[piece_move.start as usize] = Piece {
-        piece_type: PieceType::None,
-        piece_colour: PieceColour::None,
-    }; // remove piece at old position
-
-    let mut pawn_dir = -1; // direction of pawn travel
-    let mut pawn_start = 6; // start rank of pawn
-    if start_piece.piece_colour == PieceColour::Black {
-        pawn_dir = 1;
-        pawn_start = 1;
-    }; // black pawn values
-
-    // -- en passant stuff --
-
-    if piece_move.flag == MoveFlag::EnPassant {
-        board[(en_passant_move - (8 * pawn_dir)) as usize] = Piece {
-            piece_type: PieceType::None,
-            piece_colour: PieceColour::None,
-        }; // remove piece that is taken by en passant
-    }
-
-    if start_piece.piece_type == PieceType::Pawn
-        && piece_move.start / 8 == pawn_start
-        && piece_move.start + (16 * pawn_dir) == piece_move.end
-    {
-        // make this pos next en passant move if its a double pawn push
-        new_en_passant = piece_move.end - (8 * pawn_dir);
-    }
-
-    // -- promotion stuff --
-
-    match piece_move.flag {
-        MoveFlag::RookPromo => {
-            board[piece_move.end as usize] = Piece {
-                piece_type: PieceType::Rook,
-                piece_colour: start_piece.piece_colour,
-            }
-        } // change pawn to rook
-        MoveFlag::KnightPromo => {
-            board[piece_move.end as usize] = Piece {
-                piece_type: PieceType::Knight,
-                piece_colour: start_piece.piece_colour,
-            }
-        } // change pawn to knight
-        MoveFlag::BishopPromo => {
-            board[piece_move.end as usize] = Piece {
-                piece_type: PieceType::Bishop,
-                piece_colour: start_piece.piece_colour,
-            }
-        } // change pawn to bishop
-        MoveFlag::QueenPromo => {
-            board[piece_move.end as usize] = Piece {
-                piece_type: PieceType::Queen,
-                piece_colour: start_piece.piece_colour,
-            }
-        } // change pawn to queen
-        _ => (),
-    }
-
-    // -- king index changes --
-
-    if start_piece.piece_type == PieceType::King {
-        match start_piece.piece_colour {
-            PieceColour::White => king_indexs.0 = piece_move.end,
-            PieceColour::Black => king_indexs.1 = piece_move.end,
-            PieceColour::None => (),
-        }
-    }
-
-    // -- castling stuff --
-
-    // remove castling rights if rook/king is moving
-    match start_piece.piece_type {
-        PieceType::King => {
-            // remove all castling rights for that colour
-            match start_piece.piece_colour {
-                PieceColour::White => {
-                    castling_rights.0 = false;
-                    castling_rights.1 = false;
-                }
-                PieceColour::Black => {
-                    castling_rights.2 = false;
-                    castling_rights.3 = false;
-                }
-                _ => (),
-            }
-        }
-        PieceType::Rook => {
-            // remove castling rights for that side
-            match piece_move.start % 8 {
-                0 => {
-                    // queen side
-                    match start_piece.piece_colour {
-                        PieceColour::White => {
-                            // White
-                            if piece_move.start / 8 == 7 {
-                                castling_rights.1 = false
-                            }
-                        }
-                        PieceColour::Black => {
-                            // Black
-                            if piece_move.start / 8 == 0 {
-                                castling_rights.3 = false
-                            }
-                        }
-                        _ => (),
-                    }
-                }
-                7 => {
-                    // king side
-                    match start_piece.piece_colour {
-                        PieceColour::White => {
-                            // White
-                            if piece_move.start / 8 == 7 {
-                                castling_rights.0 = false
-                            }
-                        }
-                        PieceColour::Black => {
-                            // Black
-                            if piece_move.start / 8 == 0 {
-                                castling_rights.2 = false
-                            }
-                        }
-                        _ => (),
-                    }
-                }
-                _ => (),
-            }
-        }
-        _ => (),
-    }
-    // remove castling rights if rook is captured
-    if capture_piece.piece_type == PieceType::Rook {
-        match piece_move.end % 8 {
-            0 => {
-                // queen side
-                match capture_piece.piece_colour {
-                    PieceColour::White => {
-                        // white
-                        if piece_move.end / 8 == 7 {
-                            castling_rights.1 = false
-                        }
-                    }
-                    PieceColour::Black => {
-                        // Black
-                        if piece_move.end / 8 == 0 {
-                            castling_rights.3 = false
-                        }
-                    }
-                    _ => (),
-                }
-            }
-            7 => {
-                // king side
-                match capture_piece.piece_colour {
-                    PieceColour::White => {
-                        // White
-                        if piece_move.end / 8 == 7 {
-                            castling_rights.0 = false
-                        }
-                    }
-                    PieceColour::Black => {
-                        // Black
-                        if piece_move.end / 8 == 0 {
-                            castling_rights.2 = false
-                        }
-                    }
-                    _ => (),
-                }
-            }
-            _ => (),
-        }
-    }
-
-    // moving rook if move is castling
-    if piece_move.flag == MoveFlag::Castling {
-        let side = if piece_move.start - piece_move.end > 0 {
-            -1
-        } else {
-            1
-        }; // which side the king is castling on
-
-        match side {
-            -1 => {
-                // queen side
-                let rook_index = (piece_move.start / 8) * 8;
-
-                board[(piece_move.end + 1) as usize] = board[rook_index as usize];
-                board[rook_index as usize] = Piece {
-                    piece_type: PieceType::None,
-                    piece_colour: PieceColour::None,
-                };
-            }
-            1 => {
-                // king side
-                let rook_index = ((piece_move.start / 8) * 8) + 7;
-
-                board[(piece_move.end - 1) as usize] = board[rook_index as usize];
-                board[rook_index as usize] = Piece {
-                    piece_type: PieceType::None,
-                    piece_colour: PieceColour::None,
-                };
-            }
-            _ => (),
-        }
-    }
-
-    (board.to_vec(), new_en_passant, king_indexs, castling_rights)
-}
-
-// ------- CHECK AND CHECKMATE -------
-
-fn check_for_checkmate(
-    board: &mut Vec<Piece>,
-    en_passant_move: i32,
-    king_indexs: (i32, i32),
-    colours_turn: PieceColour,
-    castling_rights: (bool, bool, bool, bool),
-) -> bool {
-    // returns true if in checkmate
-    let mut legal_moves: Vec<Move> = Vec::new(); // all legal moves that the player can take
-
-    for index in 0i32..64i32 {
-        if board[index as usize].piece_colour == colours_turn {
-            // piece is players
-            legal_moves.append(&mut legal_move_gen(
-                board,
-                index,
-                en_passant_move,
-                king_indexs,
-                castling_rights,
-            ))
-        }
-    }
-
-    if legal_moves.len() == 0 {
-        // return true if no legal moves that can be made
-        return true;
-    } else {
-        return false;
-    }
-}
-
 // ------- INPUT -------
 
-fn promotion_type_input(message: &str, can_quit: bool) -> MoveFlag {
-    // returns move flag for which type of promotion selected
+fn promotion_type_input(message: &str, can_quit: bool, king_promotion: bool) -> MoveFlag {
+    // returns move flag for which type of promotion selected (king only when allowed, as in antichess)
     loop {
         let mut input = String::new();
         println!("\n{}: ", message); // print message that goes with input
@@ -1306,68 +617,64 @@ fn promotion_type_input(message: &str, can_quit: bool) -> MoveFlag {
             "knight" => return MoveFlag::KnightPromo,
             "bishop" => return MoveFlag::BishopPromo,
             "queen" => return MoveFlag::QueenPromo,
+            "king" if king_promotion => return MoveFlag::KingPromo,
             _ => println!("-- Pawn cannot promote to {}! --", input.trim()),
         }
     }
 }
 
-fn algebraic_notation_input(message: &str, can_quit: bool, can_save: bool) -> i32 {
-    // returns input from algebraic notations
-    let error_message = "\x1b[41m--FAILED TO READ INPUT LINE--\x1b[0m";
+fn text_input(message: &str) -> String {
+    // returns a line entered by the player (without surrounding whitespace)
+    let mut input = String::new();
+    println!("\n{}: ", message); // Print message that goes with input
+    io::stdin()
+        .read_line(&mut input)
+        .expect("\x1b[41m--FAILED TO READ INPUT LINE--\x1b[0m"); // get input line from console
+
+    input.trim().to_string()
+}
 
-    let mut index = 0;
+fn algebraic_notation_input(message: &str, special_inputs: &[&str]) -> Result<i32, String> {
+    // returns index of square input in algebraic notation (or the special input if one was entered, e.g: 'save')
     loop {
-        let mut input = String::new();
-        println!("\n{}: ", message); // Print message that goes with input
-        io::stdin().read_line(&mut input).expect(error_message); // get input line from console
+        let input = text_input(message).to_lowercase();
 
-        if can_quit && input == "quit\n" {
-            // go back to piece selection (input 'quit')
-            return -1;
+        if special_inputs.contains(&input.as_str()) {
+            // input was special (e.g: 'quit' to go back to piece selection)
+            return Err(input);
         }
 
-        if can_save && input == "save\n" {
-            // print out fen string (input 'save')
-            return -1;
+        match square_index(&input) {
+            Some(index) => return Ok(index), // in the form letter-number
+            None => println!("-- Not in Algebraic Notation! --"),
         }
+    }
+}
 
-        if input.chars().nth(0).expect(error_message).is_alphabetic()
-            && input.chars().nth(1).expect(error_message).is_numeric()
-            && input.trim().len() == 2
-        {
-            // Making sure input is in the form letter-number
-            let character_part = input.chars().nth(0).expect(error_message); // X coordinate
-            let number_part = input
-                .chars()
-                .nth(1)
-                .expect(error_message)
-                .to_digit(10)
-                .unwrap(); // Y coordinate
-
-            match character_part.to_lowercase().to_string().as_str() {
-                // matching letter to number
-                "a" => index += 0,
-                "b" => index += 1,
-                "c" => index += 2,
-                "d" => index += 3,
-                "e" => index += 4,
-                "f" => index += 5,
-                "g" => index += 6,
-                "h" => index += 7,
-                _ => {
-                    println!("-- Not in Algebraic Notation! --");
-                    continue;
-                } // not between a-h
-            }
+fn number_input(message: &str) -> u32 {
+    // returns a whole number entered by the player
+    loop {
+        let mut input = String::new();
+        println!("\n{}: ", message); // print message that goes with input
+        io::stdin()
+            .read_line(&mut input)
+            .expect("\x1b[41m--FAILED TO READ INPUT LINE--\x1b[0m"); // get input line from console
 
-            index += (8 - number_part as i32) * 8; // calculate index
+        match input.trim().parse::<u32>() {
+            Ok(n) => return n,
+            Err(_) => println!("-- Not a whole number! --"),
+        }
+    }
+}
 
-            break;
-        } else {
-            println!("-- Not in Algebraic Notation! --"); // not in form letter-number
+fn integer_input(message: &str) -> i32 {
+    // returns a whole number entered by the player (can be negative)
+    loop {
+        match text_input(message).parse::<i32>() {
+            Ok(n) => return n,
+            Err(_) => println!("-- Not a whole number! --"),
         }
     }
-    index
 }
 
 fn menu_selection(options: Vec<&str>) -> i32 {
@@ -1401,79 +708,267 @@ fn menu_selection(options: Vec<&str>) -> i32 {
 }
 
 fn main() {
-    let mut fen_string = String::from("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq -"); // initial chess position in fen form
+    if env::args().any(|argument| argument == "--uci") {
+        // run as an engine for chess guis instead of the terminal game
+        uci::run(io::stdin().lock(), io::stdout());
+        return;
+    }
+
+    let piece_art = load_board_art("res/Board_Art.txt"); // load art from file
+    let mut game = Game::new(Position::start()); // the position plus the moves played from it
 
     loop {
         // start menu
-        let selection = menu_selection(vec!["Play", "Load Fen", "Quit"]);
+        let selection = menu_selection(vec![
+            "Play",
+            "Play Chess960",
+            "Play Variant",
+            "Load Fen",
+            "Load PGN",
+            "Perft Divide",
+            "Quit",
+        ]);
 
         match selection {
-            1 => break,                    // start game
-            2 => fen_string = input_fen(), // gets input of fen
-            3 => process::exit(1),         // exit the program
-            _ => (),                       // invalid input (somethings gone wrong)
+            1 => break, // start game
+            2 => {
+                // start game from a shuffled back rank
+                game = Game::new(input_chess960());
+                break;
+            }
+            3 => {
+                // start game with different rules
+                game = Game::new(choose_variant().start_position());
+                break;
+            }
+            4 => game = Game::new(input_fen()), // gets input of fen
+            5 => {
+                // gets a game from a pgn file
+                if let Some(loaded_game) = input_pgn(&piece_art) {
+                    game = loaded_game;
+                    break;
+                }
+            }
+            6 => perft_divide(&game.position), // count moves from the current position
+            7 => process::exit(1),             // exit the program
+            _ => (),                           // invalid input (somethings gone wrong)
         }
     }
 
-    let (mut board, mut colours_turn, mut castling_rights, mut en_passant_move) =
-        decode_fen(&fen_string.trim()); // get board layout, colours turn, castling rights and en passant target from fen string
+    let mut players = [
+        choose_player(PieceColour::White, &piece_art),
+        choose_player(PieceColour::Black, &piece_art),
+    ]; // who moves for white and black
 
-    let piece_art = load_board_art("res/Board_Art.txt"); // load art from file
-
-    let mut king_indexes: (i32, i32) = (-1, -1); // indexs of the kings
-    for index in 0i32..64i32 {
-        // looping over every piece to find the kings
-        let piece = board[index as usize];
-
-        if piece.piece_type == PieceType::King {
-            match piece.piece_colour {
-                PieceColour::White => king_indexes.0 = index, // white king
-                PieceColour::Black => king_indexes.1 = index, // black king
-                PieceColour::None => (), // somthing has gone wrong if this happens ;-;
+    loop {
+        match game.status() {
+            GameStatus::Ongoing { .. } if game.recorded_result().is_some() => {
+                // loaded game that was finished when it was written down (e.g: by resignation)
+                print_board(
+                    &game.position.board,
+                    &[],
+                    &piece_art,
+                    &move_list_text(&game),
+                );
+                println!(
+                    "\x1b[47;30m-- THE GAME ENDED {} --\x1b[0m",
+                    game.result().token()
+                );
+                break;
+            }
+            GameStatus::Ongoing { in_check } => {
+                if in_check {
+                    println!(
+                        "\x1b[43;30m-- {:?} IS IN CHECK --\x1b[0m",
+                        game.position.colours_turn
+                    );
+                }
+            }
+            game_over => {
+                // show the final board and the result
+                print_board(
+                    &game.position.board,
+                    &[],
+                    &piece_art,
+                    &move_list_text(&game),
+                );
+                match game_over {
+                    GameStatus::Checkmate(PieceColour::White) => {
+                        println!("\x1b[42;30m-- CHECKMATE, WHITE HAS WON --\x1b[0m")
+                    }
+                    GameStatus::Checkmate(PieceColour::Black) => {
+                        println!("\x1b[42;30m-- CHECKMATE, BLACK HAS WON --\x1b[0m")
+                    }
+                    GameStatus::VariantWin(winner) => println!(
+                        "\x1b[42;30m-- {}, {} HAS WON --\x1b[0m",
+                        game.position.variant.win_description(),
+                        format!("{:?}", winner).to_uppercase()
+                    ),
+                    GameStatus::Draw(draw_reason) => println!(
+                        "\x1b[47;30m-- {}, THE GAME IS A DRAW --\x1b[0m",
+                        draw_reason_name(draw_reason)
+                    ),
+                    _ => println!("\x1b[47;30m-- STALEMATE, THE GAME IS A DRAW --\x1b[0m"),
+                }
+                break;
             }
         }
-    }
 
-    loop {
-        if check_for_checkmate(
-            &mut board,
-            en_passant_move,
-            king_indexes,
-            colours_turn,
-            castling_rights,
-        ) {
-            // check if current player if in checkmate
+        let colour = game.position.colours_turn;
+        let player = turn_index(&game);
+        let opponent = 1 - player;
+
+        if let Some(draw_reason) = game.claimable_draw() {
+            // let the player to move claim a draw
+            if players[player].claims_draw(&game, draw_reason) {
+                game.claim_draw();
+                print_board(
+                    &game.position.board,
+                    &[],
+                    &piece_art,
+                    &move_list_text(&game),
+                );
+                println!(
+                    "\x1b[47;30m-- {} CLAIMED, THE GAME IS A DRAW --\x1b[0m",
+                    draw_reason_name(draw_reason)
+                );
+                break;
+            }
+        }
 
-            print_board(&board, &Vec::new(), &piece_art);
-            match colours_turn {
-                PieceColour::White => {
-                    println!("\x1b[42;30m-- BLACK HAS WON --\x1b[0m");
-                    break;
-                }
-                PieceColour::Black => {
-                    println!("\x1b[42;30m-- WHITE HAS WON --\x1b[0m");
+        let legal_moves = game.legal_moves();
+
+        match players[player].choose_action(&game, &legal_moves) {
+            PlayerAction::Move(piece_move) if legal_moves.contains(&piece_move) => {
+                if !players[player].is_human() {
+                    // say what was played since nobody typed it
+                    println!(
+                        "{:?} ({}) plays {}",
+                        colour,
+                        players[player].name(),
+                        game.position.move_to_san(piece_move)
+                    );
+                }
+                game.make_move(piece_move); // make move (also swaps whos turn it is)
+            }
+            PlayerAction::Move(piece_move) => {
+                println!(
+                    "-- {} can't play {}, so resigns --",
+                    players[player].name(),
+                    piece_move.to_long_algebraic()
+                );
+                game.resign(colour);
+            }
+            PlayerAction::Undo => {
+                if !game.undo() {
+                    println!("-- No moves to undo! --");
+                }
+                while !players[turn_index(&game)].is_human() && game.undo() {} // back to a person's turn
+            }
+            PlayerAction::Redo => {
+                if !game.redo() {
+                    println!("-- No moves to redo! --");
+                }
+                while !players[turn_index(&game)].is_human() && game.redo() {}
+            }
+            PlayerAction::Resign => game.resign(colour),
+            PlayerAction::OfferDraw => {
+                if players[opponent].accepts_draw(&game) {
+                    game.agree_draw();
+                    print_board(
+                        &game.position.board,
+                        &[],
+                        &piece_art,
+                        &move_list_text(&game),
+                    );
+                    println!("\x1b[47;30m-- DRAW AGREED, THE GAME IS A DRAW --\x1b[0m");
                     break;
                 }
-                _ => (),
+                println!("-- {:?} declined the draw --", colour.opponent());
             }
+        }
 
-            main();
+        if game.resigned().is_some() {
+            print_board(
+                &game.position.board,
+                &[],
+                &piece_art,
+                &move_list_text(&game),
+            );
+            println!(
+                "\x1b[42;30m-- {:?} RESIGNED, {:?} HAS WON --\x1b[0m",
+                colour,
+                colour.opponent()
+            );
+            break;
         }
+    }
+
+    println!("\nResult: {}\n", game.result().token());
+    while menu_selection(vec!["Save PGN", "Quit"]) == 1 {
+        // game is over, let the players keep a record of it
+        save_pgn(&game);
+    }
+}
+
+fn perft_divide(position: &Position) {
+    // prints the number of positions reached after each move (for checking move generation)
+    println!("\nFen String:\n'{}'", position.to_fen());
+    let depth = number_input("Enter the depth to count to");
+    let megabytes = number_input("Enter the hash table size in MB (0 to count without one)");
+
+    let start_time = Instant::now();
+    let mut table: HashTable<u64> = HashTable::with_megabytes(megabytes as usize);
+    let move_counts = match megabytes {
+        0 => perft::divide(position, depth),
+        _ => perft::divide_hashed(position, depth, &mut table),
+    };
+    let mut total_nodes = 0;
+
+    for (piece_move, nodes) in &move_counts {
+        println!("{}: {}", piece_move.to_long_algebraic(), nodes);
+        total_nodes += nodes;
+    }
+
+    println!(
+        "\nMoves: {}\nNodes: {}\nTime: {:.3}s\n",
+        move_counts.len(),
+        total_nodes,
+        start_time.elapsed().as_secs_f64()
+    );
+
+    if megabytes != 0 {
+        let stats = table.stats();
+        println!(
+            "Hash: {} entries, {} probes, {} hits ({:.1}%), {} stores, {} overwrites\n",
+            table.capacity(),
+            stats.probes,
+            stats.hits,
+            stats.hit_rate() * 100.0,
+            stats.stores,
+            stats.overwrites
+        );
+    }
+}
+
+fn turn_index(game: &Game) -> usize {
+    // returns the index of the player to move in the players array (white first)
+    match game.position.colours_turn {
+        PieceColour::White => 0,
+        _ => 1,
+    }
+}
 
-        (
-            board,
-            en_passant_move,
-            colours_turn,
-            king_indexes,
-            castling_rights,
-        ) = selection_iteration(
-            board,
-            colours_turn,
-            en_passant_move,
-            &piece_art,
-            king_indexes,
-            castling_rights,
-        ); // loop through main loop again
+fn draw_reason_name(draw_reason: DrawReason) -> &'static str {
+    // returns the name of a draw for the end of game message
+    match draw_reason {
+        DrawReason::FiftyMoveRule => "FIFTY MOVE RULE",
+        DrawReason::SeventyFiveMoveRule => "SEVENTY FIVE MOVE RULE",
+        DrawReason::ThreefoldRepetition => "THREEFOLD REPETITION",
+        DrawReason::FivefoldRepetition => "FIVEFOLD REPETITION",
+        DrawReason::InsufficientMaterial => "INSUFFICIENT MATERIAL",
+        DrawReason::Agreement => "AGREEMENT",
+        DrawReason::VariantRule => "VARIANT RULES",
     }
 }
 // 1k2r2r/1p3p1p/1Np3p1/3b4/P5n1/2PP4/3K2p1/4Q3 w - -
@@ -0,0 +1,303 @@
+use crate::atomic::atomic_king_safe_after;
+use crate::bitboard::{
+    bishop_attacks, colour_index, king_attacks, knight_attacks, pawn_attacks, queen_attacks,
+    rook_attacks, square_bit, squares, Bitboard,
+};
+use crate::crazyhouse::POCKET_ORDER;
+use crate::piece::{Piece, PieceColour, PieceType};
+use crate::position::{castling_ends, Move, MoveFlag, Position};
+use crate::variant::Variant;
+
+const RANK_1: Bitboard = 0xff00_0000_0000_0000; // white back rank (indexes 56-63)
+const RANK_8: Bitboard = 0x0000_0000_0000_00ff; // black back rank (indexes 0-7)
+const ALL_SQUARES: Bitboard = !0;
+
+pub(crate) fn legal_move_gen(position: &Position, from_squares: Bitboard) -> Vec<Move> {
+    // generates legal moves for the player to move, only for pieces on the given squares
+    let mut moves = sudo_legal_move_gen(position, from_squares);
+
+    match position.variant {
+        Variant::Atomic => moves.retain(|sudo_move| atomic_king_safe_after(position, *sudo_move)), // explosions change what is safe
+        Variant::Antichess => forced_captures(position, &mut moves, from_squares), // the king is just another piece
+        _ => moves.retain(|sudo_move| king_safe_after(position, *sudo_move)), // removes any piece moves that result in check
+    }
+    if position.variant != Variant::Standard {
+        moves.retain(|legal_move| position.variant_allows(*legal_move)); // moves the variant's rules forbid
+    }
+    moves
+}
+
+fn forced_captures(position: &Position, moves: &mut Vec<Move>, from_squares: Bitboard) {
+    // keeps only captures if the player to move has any (antichess), looking at every piece even if only some were asked for
+    let is_capture = |piece_move: &Move| {
+        piece_move.flag == MoveFlag::EnPassant
+            || position.board[piece_move.end as usize].piece_type != PieceType::None
+    };
+
+    let must_capture = match from_squares {
+        ALL_SQUARES => moves.iter().any(is_capture),
+        _ => sudo_legal_move_gen(position, ALL_SQUARES)
+            .iter()
+            .any(is_capture),
+    };
+
+    if must_capture {
+        moves.retain(is_capture);
+    }
+}
+
+pub(crate) fn drop_move_gen(position: &Position) -> Vec<Move> {
+    // generates legal drops of pieces from the pocket of the player to move (crazyhouse)
+    let colour = position.colours_turn;
+    let empty = !position.bitboards.occupied;
+    let mut drops: Vec<Move> = Vec::new();
+
+    for piece_type in POCKET_ORDER {
+        if position.pocket_count(colour, piece_type) == 0 {
+            continue;
+        }
+
+        let targets = match piece_type {
+            PieceType::Pawn => empty & !(RANK_1 | RANK_8), // pawns can't be dropped on the back ranks
+            _ => empty,
+        };
+
+        for end in squares(targets) {
+            drops.push(Move {
+                start: end,
+                end,
+                flag: MoveFlag::Drop(piece_type),
+            });
+        }
+    }
+
+    drops.retain(|drop| king_safe_after(position, *drop)); // only drops that block a check are allowed when in check
+    drops
+}
+
+fn sudo_legal_move_gen(position: &Position, from_squares: Bitboard) -> Vec<Move> {
+    // generates moves without respect to check
+    let mut piece_moves: Vec<Move> = Vec::with_capacity(64);
+    let colour = position.colours_turn;
+
+    if colour == PieceColour::None {
+        return piece_moves;
+    }
+
+    let bitboards = &position.bitboards;
+    let own = bitboards.colours[colour_index(colour)];
+    let enemy = bitboards.colours[colour_index(colour.opponent())];
+    let occupied = bitboards.occupied;
+
+    // -- pawns --
+
+    let (dir, start_rank, promotion_rank) = match colour {
+        PieceColour::White => (-1, 6, RANK_8),
+        _ => (1, 1, RANK_1),
+    };
+
+    for index in squares(bitboards.piece_bitboard(PieceType::Pawn, colour) & from_squares) {
+        let mut targets: Bitboard = 0;
+        let forward = index + (8 * dir);
+
+        if (0..64).contains(&forward) && occupied & square_bit(forward) == 0 {
+            // single move forward
+            targets |= square_bit(forward);
+
+            let double_forward = index + (16 * dir);
+            if index / 8 == start_rank && occupied & square_bit(double_forward) == 0 {
+                // double move forward when on starting rank
+                targets |= square_bit(double_forward);
+            }
+        }
+
+        targets |= pawn_attacks(index, colour) & enemy; // taking diagonally on each side
+
+        for end in squares(targets) {
+            let flag = match square_bit(end) & promotion_rank != 0 {
+                true => MoveFlag::Promotion, // last rank so promotion
+                false => MoveFlag::None,
+            };
+            piece_moves.push(Move {
+                start: index,
+                end,
+                flag,
+            });
+        }
+
+        if position.en_passant_move != -1
+            && pawn_attacks(index, colour) & square_bit(position.en_passant_move) != 0
+        {
+            // en passant
+            piece_moves.push(Move {
+                start: index,
+                end: position.en_passant_move,
+                flag: MoveFlag::EnPassant,
+            });
+        }
+    }
+
+    // -- knights, bishops, rooks, queens, kings --
+
+    for piece_type in [
+        PieceType::Knight,
+        PieceType::Bishop,
+        PieceType::Rook,
+        PieceType::Queen,
+        PieceType::King,
+    ] {
+        for index in squares(bitboards.piece_bitboard(piece_type, colour) & from_squares) {
+            let attacks = match piece_type {
+                PieceType::Knight => knight_attacks(index),
+                PieceType::Bishop => bishop_attacks(index, occupied),
+                PieceType::Rook => rook_attacks(index, occupied),
+                PieceType::Queen => queen_attacks(index, occupied),
+                _ => king_attacks(index),
+            };
+
+            for end in squares(attacks & !own) {
+                piece_moves.push(Move {
+                    start: index,
+                    end,
+                    flag: MoveFlag::None,
+                });
+            }
+        }
+    }
+
+    // -- castling --
+
+    let king_index = match colour {
+        PieceColour::White => position.king_indexes.0,
+        _ => position.king_indexes.1,
+    };
+
+    if king_index != -1
+        && from_squares & square_bit(king_index) != 0
+        && position.variant != Variant::Antichess
+    {
+        castling_move_gen(position, king_index, &mut piece_moves);
+    }
+
+    piece_moves
+}
+
+fn castling_move_gen(position: &Position, king_index: i32, piece_moves: &mut Vec<Move>) {
+    // adds castling moves (king can't be in check, pass through or land on an attacked square)
+    // the king and rook can start anywhere on the back rank (chess960) but always land on the usual squares
+    let colour = position.colours_turn;
+    let opponent = colour.opponent();
+    let bitboards = &position.bitboards;
+
+    let (back_rank_start, king_side_right, queen_side_right) = match colour {
+        PieceColour::White => (56, position.castling_rights.0, position.castling_rights.1),
+        _ => (0, position.castling_rights.2, position.castling_rights.3),
+    };
+
+    if king_index / 8 != back_rank_start / 8 || position.is_in_check() {
+        return;
+    }
+
+    let rooks = bitboards.piece_bitboard(PieceType::Rook, colour);
+    let enemies = bitboards.colours[colour_index(opponent)];
+    let enemy_king = bitboards.piece_bitboard(PieceType::King, opponent);
+    let atomic = position.variant == Variant::Atomic;
+
+    for (can_castle, king_side) in [(king_side_right, true), (queen_side_right, false)] {
+        let rook_index = position.castling_rook(colour, king_side);
+        if !can_castle || rooks & square_bit(rook_index) == 0 {
+            continue;
+        }
+
+        let (king_end, rook_end) = castling_ends(king_index, king_side);
+        let others = bitboards.occupied & !square_bit(king_index) & !square_bit(rook_index); // everything but the castling pieces
+
+        if (rank_span(king_index, king_end) | rank_span(rook_index, rook_end)) & others != 0 {
+            continue; // pieces in the way
+        }
+
+        if squares(rank_span(king_index, king_end)).any(|index| {
+            // squares passed through are judged with the rook still home, the one landed on after the rook has moved
+            // (only makes a difference in atomic, where the landing square can be next to the other king)
+            let occupied = match index == king_end {
+                true => others | square_bit(rook_end),
+                false => bitboards.occupied & !square_bit(king_index),
+            };
+            bitboards.attackers_to(index, occupied) & enemies != 0
+                && !(atomic && king_attacks(index) & enemy_king != 0) // next to the other king is safe in atomic
+        }) {
+            continue; // king would pass through or land in check
+        }
+
+        piece_moves.push(Move {
+            start: king_index,
+            end: match position.chess960 {
+                true => rook_index, // king takes its own rook (the king might not move at all)
+                false => king_end,
+            },
+            flag: MoveFlag::Castling,
+        });
+    }
+}
+
+fn rank_span(from: i32, to: i32) -> Bitboard {
+    // returns the squares from one square to another on the same rank (both included)
+    (from.min(to)..=from.max(to)).fold(0, |span, index| span | square_bit(index))
+}
+
+fn king_safe_after(position: &Position, sudo_move: Move) -> bool {
+    // returns true if the move doesn't leave the players own king attacked
+    if sudo_move.flag == MoveFlag::Castling {
+        return true; // already checked when generating castling
+    }
+
+    let colour = position.colours_turn;
+    let moving_piece = match sudo_move.flag {
+        MoveFlag::Drop(piece_type) => Piece {
+            piece_type,
+            piece_colour: colour,
+        },
+        _ => position.board[sudo_move.start as usize],
+    };
+    let captured_piece = position.board[sudo_move.end as usize];
+
+    let king_index = match moving_piece.piece_type {
+        PieceType::King => sudo_move.end, // king moves with the move being checked
+        _ => match colour {
+            PieceColour::White => position.king_indexes.0,
+            _ => position.king_indexes.1,
+        },
+    };
+
+    if king_index == -1 {
+        return true; // no king to put in check
+    }
+
+    // sudo make move on a copy of the bitboards
+    let mut bitboards = position.bitboards;
+    if !matches!(sudo_move.flag, MoveFlag::Drop(_)) {
+        bitboards.toggle(sudo_move.start, moving_piece); // dropped pieces don't leave a square
+    }
+    bitboards.toggle(sudo_move.end, captured_piece);
+    bitboards.toggle(sudo_move.end, moving_piece);
+
+    if sudo_move.flag == MoveFlag::EnPassant {
+        // remove piece that is taken by en passant
+        let taken_index = match colour {
+            PieceColour::White => sudo_move.end + 8,
+            _ => sudo_move.end - 8,
+        };
+        bitboards.toggle(taken_index, position.board[taken_index as usize]);
+    }
+
+    !bitboards.is_attacked(king_index, colour.opponent())
+}
+
+pub(crate) fn square_attacked(
+    position: &Position,
+    index: i32,
+    attacking_side: PieceColour,
+) -> bool {
+    // returns true if any piece of the attacking side attacks the square
+    position.bitboards.is_attacked(index, attacking_side)
+}
@@ -0,0 +1,93 @@
+use crate::hash_table::HashTable;
+use crate::position::{Move, Position};
+
+pub fn perft(position: &Position, depth: u32) -> u64 {
+    // counts the leaf nodes of the move tree to the given depth (for checking move generation)
+    perft_recursive(&mut position.clone(), depth)
+}
+
+fn perft_recursive(position: &mut Position, depth: u32) -> u64 {
+    // perft making and unmaking moves on the one position
+    if depth == 0 {
+        return 1;
+    }
+
+    let legal_moves = position.legal_moves_with_promotions();
+
+    if depth == 1 {
+        return legal_moves.len() as u64; // no need to make the last moves
+    }
+
+    let mut nodes = 0;
+    for legal_move in legal_moves {
+        let undo_info = position.make_move(legal_move);
+        nodes += perft_recursive(position, depth - 1);
+        position.unmake_move(undo_info);
+    }
+
+    nodes
+}
+
+pub fn divide(position: &Position, depth: u32) -> Vec<(Move, u64)> {
+    // returns the perft count below each legal move (depth counts the move itself)
+    let mut position = position.clone();
+    let mut move_counts: Vec<(Move, u64)> = Vec::new();
+
+    for legal_move in position.legal_moves_with_promotions() {
+        let undo_info = position.make_move(legal_move);
+        move_counts.push((
+            legal_move,
+            perft_recursive(&mut position, depth.saturating_sub(1)),
+        ));
+        position.unmake_move(undo_info);
+    }
+
+    move_counts
+}
+
+pub fn perft_hashed(position: &Position, depth: u32, table: &mut HashTable<u64>) -> u64 {
+    // perft that remembers the count below each position it has seen (transpositions are only counted once)
+    perft_hashed_recursive(&mut position.clone(), depth, table)
+}
+
+fn perft_hashed_recursive(position: &mut Position, depth: u32, table: &mut HashTable<u64>) -> u64 {
+    // perft_recursive looking in the table before searching a position
+    if depth <= 1 {
+        return perft_recursive(position, depth); // cheaper to count than to look up
+    }
+
+    if let Some(nodes) = table.probe(position.hash, depth) {
+        return nodes;
+    }
+
+    let mut nodes = 0;
+    for legal_move in position.legal_moves_with_promotions() {
+        let undo_info = position.make_move(legal_move);
+        nodes += perft_hashed_recursive(position, depth - 1, table);
+        position.unmake_move(undo_info);
+    }
+
+    table.store(position.hash, depth, nodes);
+    nodes
+}
+
+pub fn divide_hashed(
+    position: &Position,
+    depth: u32,
+    table: &mut HashTable<u64>,
+) -> Vec<(Move, u64)> {
+    // divide using a table of counts (see perft_hashed)
+    let mut position = position.clone();
+    let mut move_counts: Vec<(Move, u64)> = Vec::new();
+
+    for legal_move in position.legal_moves_with_promotions() {
+        let undo_info = position.make_move(legal_move);
+        move_counts.push((
+            legal_move,
+            perft_hashed_recursive(&mut position, depth.saturating_sub(1), table),
+        ));
+        position.unmake_move(undo_info);
+    }
+
+    move_counts
+}
@@ -0,0 +1,500 @@
+use std::time::{SystemTime, UNIX_EPOCH};
+use std::{error, fmt};
+
+use crate::fen::FenError;
+use crate::game::Game;
+use crate::piece::PieceColour;
+use crate::position::Position;
+use crate::san::MoveParseError;
+use crate::status::GameStatus;
+use crate::variant::Variant;
+
+const LINE_LENGTH: usize = 80; // longest line of move text (as recommended by the pgn standard)
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum GameResult {
+    // outcome of a game as written at the end of a pgn
+    WhiteWins,
+    BlackWins,
+    Draw,
+    Ongoing, // game not finished (or result unknown)
+}
+
+impl GameResult {
+    pub fn token(self) -> &'static str {
+        // returns the pgn result token (e.g: "1-0")
+        match self {
+            GameResult::WhiteWins => "1-0",
+            GameResult::BlackWins => "0-1",
+            GameResult::Draw => "1/2-1/2",
+            GameResult::Ongoing => "*",
+        }
+    }
+
+    pub fn from_token(token: &str) -> Option<GameResult> {
+        // returns the result a pgn result token stands for (None if it isn't one)
+        match token {
+            "1-0" => Some(GameResult::WhiteWins),
+            "0-1" => Some(GameResult::BlackWins),
+            "1/2-1/2" => Some(GameResult::Draw),
+            "*" => Some(GameResult::Ongoing),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PgnTags {
+    // the seven tag roster (Result is taken from the game itself)
+    pub event: String,
+    pub site: String,
+    pub date: String, // YYYY.MM.DD (unknown parts are written as '?')
+    pub round: String,
+    pub white: String,
+    pub black: String,
+}
+
+impl PgnTags {
+    pub fn new() -> PgnTags {
+        // returns tags for a game played today with everything else unknown
+        PgnTags {
+            event: String::from("?"),
+            site: String::from("?"),
+            date: todays_date(),
+            round: String::from("?"),
+            white: String::from("?"),
+            black: String::from("?"),
+        }
+    }
+}
+
+impl Default for PgnTags {
+    fn default() -> Self {
+        PgnTags::new()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PgnError {
+    // reasons a pgn can't be loaded (line is the line of the pgn the problem is on, counting from 1)
+    BadTag {
+        line: usize,
+    },
+    BadFen(FenError), // FEN tag isn't a valid fen string
+    IllegalMove {
+        text: String,
+        line: usize,
+        reason: MoveParseError,
+    },
+    UnclosedComment {
+        line: usize,
+    },
+    UnbalancedVariation {
+        line: usize,
+    }, // a '(' without a ')' or the other way around
+    ResultMismatch {
+        line: usize,
+    }, // result at the end of the moves doesn't match the Result tag or how the moves end
+}
+
+impl fmt::Display for PgnError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            PgnError::BadTag { line } => write!(f, "badly formed tag pair (on line {})", line),
+            PgnError::BadFen(fen_error) => write!(f, "FEN tag is not valid, {}", fen_error),
+            PgnError::IllegalMove { text, line, reason } => {
+                write!(f, "can't play '{}', {} (on line {})", text, reason, line)
+            }
+            PgnError::UnclosedComment { line } => {
+                write!(f, "comment is never closed (opened on line {})", line)
+            }
+            PgnError::UnbalancedVariation { line } => {
+                write!(f, "variation brackets don't match (on line {})", line)
+            }
+            PgnError::ResultMismatch { line } => write!(
+                f,
+                "result doesn't match the Result tag or the final position (on line {})",
+                line
+            ),
+        }
+    }
+}
+
+impl error::Error for PgnError {}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum PgnToken {
+    // the parts of a pgn that matter for replaying it (comments and NAGs are dropped)
+    Tag(String, String),
+    Move(String),
+    VariationStart,
+    VariationEnd,
+    Result(GameResult),
+}
+
+fn todays_date() -> String {
+    // returns the current date in pgn form (e.g: "2024.03.09")
+    let days = match SystemTime::now().duration_since(UNIX_EPOCH) {
+        Ok(time) => (time.as_secs() / 86_400) as i64,
+        Err(_) => return String::from("????.??.??"), // clock is before 1970
+    };
+
+    // convert days since 1970-01-01 to a calendar date (Howard Hinnant's civil_from_days)
+    let shifted_days = days + 719_468; // days since 0000-03-01
+    let era = shifted_days.div_euclid(146_097);
+    let day_of_era = shifted_days.rem_euclid(146_097);
+    let year_of_era =
+        (day_of_era - day_of_era / 1_460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_index = (5 * day_of_year + 2) / 153; // months counted from march
+    let day = day_of_year - (153 * month_index + 2) / 5 + 1;
+    let month = if month_index < 10 {
+        month_index + 3
+    } else {
+        month_index - 9
+    };
+    let year = year_of_era + era * 400 + if month <= 2 { 1 } else { 0 };
+
+    format!("{:04}.{:02}.{:02}", year, month, day)
+}
+
+fn is_chess960_variant(variant: &str) -> bool {
+    // returns true if a Variant tag names chess960 (it goes by a few names)
+    let variant = variant.to_lowercase().replace([' ', '-'], "");
+    variant.contains("960") || variant.contains("fischerandom") || variant.contains("fischerrandom")
+}
+
+fn tag_pair(name: &str, value: &str) -> String {
+    // returns a tag pair line (quotes and backslashes in the value are escaped)
+    let escaped_value = value.replace('\\', "\\\\").replace('"', "\\\"");
+    format!("[{} \"{}\"]\n", name, escaped_value)
+}
+
+fn read_tag(tag_text: &str, line: usize) -> Result<PgnToken, PgnError> {
+    // returns the tag pair inside the square brackets (e.g: Event "Casual Game")
+    let tag_text = tag_text.trim();
+    let name_length = tag_text
+        .find(|c: char| !(c.is_ascii_alphanumeric() || c == '_'))
+        .unwrap_or(tag_text.len());
+    let name = &tag_text[..name_length];
+    let quoted_value = tag_text[name_length..].trim();
+
+    if name.is_empty()
+        || quoted_value.len() < 2
+        || !quoted_value.starts_with('"')
+        || !quoted_value.ends_with('"')
+    {
+        return Err(PgnError::BadTag { line });
+    }
+
+    let mut value = String::new();
+    let mut escaped = false; // last character was a backslash
+    for value_char in quoted_value[1..quoted_value.len() - 1].chars() {
+        match (escaped, value_char) {
+            (false, '\\') => escaped = true,
+            (false, '"') => return Err(PgnError::BadTag { line }), // quote that isn't escaped
+            _ => {
+                value.push(value_char);
+                escaped = false;
+            }
+        }
+    }
+
+    Ok(PgnToken::Tag(name.to_string(), value))
+}
+
+fn tokenize(pgn: &str) -> Result<Vec<(usize, PgnToken)>, PgnError> {
+    // splits a pgn into tokens with the line each one is on (stops at the end of the first game)
+    let mut tokens: Vec<(usize, PgnToken)> = Vec::new();
+    let mut chars = pgn.chars().peekable();
+    let mut line = 1;
+    let mut line_start = true; // at the first character of a line ('%' escapes only count there)
+
+    while let Some(current_char) = chars.next() {
+        let token_line = line;
+
+        match current_char {
+            '\n' => {
+                line += 1;
+                line_start = true;
+                continue;
+            }
+            '%' if line_start => {
+                // escaped line (ignored)
+                while chars.next_if(|&c| c != '\n').is_some() {}
+            }
+            c if c.is_whitespace() => {
+                line_start = false;
+                continue;
+            }
+            '[' => {
+                let mut tag_text = String::new();
+                let mut in_quotes = false;
+                let mut escaped = false;
+                loop {
+                    match chars.next() {
+                        Some(']') if !in_quotes => break,
+                        Some('\n') | None => return Err(PgnError::BadTag { line }),
+                        Some(tag_char) => {
+                            if tag_char == '"' && !escaped {
+                                in_quotes = !in_quotes;
+                            }
+                            escaped = tag_char == '\\' && !escaped;
+                            tag_text.push(tag_char);
+                        }
+                    }
+                }
+                tokens.push((token_line, read_tag(&tag_text, line)?));
+            }
+            '{' => loop {
+                // comment (ignored)
+                match chars.next() {
+                    Some('}') => break,
+                    Some('\n') => line += 1,
+                    Some(_) => (),
+                    None => return Err(PgnError::UnclosedComment { line: token_line }),
+                }
+            },
+            ';' => while chars.next_if(|&c| c != '\n').is_some() {}, // comment to the end of the line
+            '$' => while chars.next_if(|c| c.is_ascii_digit()).is_some() {}, // numeric annotation glyph (ignored)
+            '(' => tokens.push((token_line, PgnToken::VariationStart)),
+            ')' => tokens.push((token_line, PgnToken::VariationEnd)),
+            '*' => tokens.push((token_line, PgnToken::Result(GameResult::Ongoing))),
+            _ => {
+                let mut symbol = String::from(current_char);
+                while let Some(symbol_char) =
+                    chars.next_if(|&c| !c.is_whitespace() && !"[]{}();$".contains(c))
+                {
+                    symbol.push(symbol_char);
+                }
+
+                if let Some(result) = GameResult::from_token(&symbol) {
+                    tokens.push((token_line, PgnToken::Result(result)));
+                } else {
+                    // move number (e.g: "12." or "12...") can be stuck to the move after it
+                    let after_number = symbol.trim_start_matches(|c: char| c.is_ascii_digit());
+                    let move_text = match after_number.starts_with('.') {
+                        true => after_number.trim_start_matches('.'),
+                        false => symbol.as_str(),
+                    };
+
+                    if !move_text.is_empty() {
+                        tokens.push((token_line, PgnToken::Move(move_text.to_string())));
+                    }
+                }
+            }
+        }
+
+        line_start = false;
+
+        if matches!(tokens.last(), Some((_, PgnToken::Result(_)))) {
+            break; // end of the first game
+        }
+    }
+
+    Ok(tokens)
+}
+
+impl Game {
+    pub fn from_pgn(pgn: &str) -> Result<(Game, PgnTags), PgnError> {
+        // returns the main line of the first game in a pgn along with its tags (every move is checked, including variations)
+        let tokens = tokenize(pgn)?;
+        let mut tags = PgnTags {
+            date: String::from("????.??.??"),
+            ..PgnTags::new()
+        };
+
+        // -- TAGS --
+
+        let mut start_fen: Option<&str> = None;
+        let mut result_tag: Option<GameResult> = None;
+        let mut variant = Variant::Standard;
+        let mut chess960 = false;
+
+        for (_, token) in tokens.iter() {
+            if let PgnToken::Tag(name, value) = token {
+                match name.as_str() {
+                    "Event" => tags.event = value.clone(),
+                    "Site" => tags.site = value.clone(),
+                    "Date" => tags.date = value.clone(),
+                    "Round" => tags.round = value.clone(),
+                    "White" => tags.white = value.clone(),
+                    "Black" => tags.black = value.clone(),
+                    "FEN" => start_fen = Some(value),
+                    "Result" => result_tag = GameResult::from_token(value),
+                    "Variant" => {
+                        chess960 = is_chess960_variant(value);
+                        variant = Variant::from_name(value).unwrap_or(Variant::Standard);
+                    }
+                    _ => (), // tags we don't use
+                }
+            }
+        }
+
+        let mut start_position = match start_fen {
+            Some(start_fen) => {
+                Position::from_fen_with_variant(start_fen, variant).map_err(PgnError::BadFen)?
+            }
+            None => variant.start_position(),
+        };
+        start_position.chess960 |= chess960;
+
+        // -- MOVE TEXT --
+
+        let mut game = Game::new(start_position.clone());
+        let mut line_position = start_position.clone(); // position the moves being read are played from
+        let mut before_last_move = start_position; // where a variation on the last move starts from
+        let mut variations: Vec<(Position, Position)> = Vec::new(); // positions to go back to when each variation ends
+
+        for (line, token) in tokens {
+            match token {
+                PgnToken::Move(text) => {
+                    let piece_move = line_position.parse_move(&text).map_err(|reason| {
+                        PgnError::IllegalMove {
+                            text: text.clone(),
+                            line,
+                            reason,
+                        }
+                    })?;
+
+                    before_last_move = line_position.clone();
+                    line_position.make_move(piece_move);
+
+                    if variations.is_empty() {
+                        game.make_move(piece_move); // only the main line is kept
+                    }
+                }
+                PgnToken::VariationStart => {
+                    variations.push((line_position.clone(), before_last_move.clone()));
+                    line_position = before_last_move.clone(); // variation replaces the last move
+                }
+                PgnToken::VariationEnd => match variations.pop() {
+                    Some((main_position, main_before_last_move)) => {
+                        line_position = main_position;
+                        before_last_move = main_before_last_move;
+                    }
+                    None => return Err(PgnError::UnbalancedVariation { line }),
+                },
+                PgnToken::Result(result) => {
+                    // has to agree with the tag and with a game the moves already ended
+                    let ended_by_moves = game.result();
+                    if result_tag.is_some_and(|result_tag| result_tag != result)
+                        || (ended_by_moves != GameResult::Ongoing && ended_by_moves != result)
+                    {
+                        return Err(PgnError::ResultMismatch { line });
+                    }
+                    if result != GameResult::Ongoing {
+                        game.set_recorded_result(result); // e.g: a resignation or agreed draw
+                    }
+                }
+                PgnToken::Tag(..) => (),
+            }
+        }
+
+        if !variations.is_empty() {
+            return Err(PgnError::UnbalancedVariation {
+                line: pgn.lines().count(),
+            });
+        }
+
+        Ok((game, tags))
+    }
+
+    pub fn result(&self) -> GameResult {
+        // returns the result of the game so far (Ongoing if it isn't over)
+        if let Some(result) = self.recorded_result() {
+            return result;
+        }
+
+        if self.claimed_draw().is_some() {
+            return GameResult::Draw;
+        }
+
+        match self.resigned() {
+            Some(PieceColour::White) => return GameResult::BlackWins,
+            Some(PieceColour::Black) => return GameResult::WhiteWins,
+            _ => (),
+        }
+
+        match self.status() {
+            GameStatus::Checkmate(PieceColour::White)
+            | GameStatus::VariantWin(PieceColour::White) => GameResult::WhiteWins,
+            GameStatus::Checkmate(_) | GameStatus::VariantWin(_) => GameResult::BlackWins,
+            GameStatus::Stalemate | GameStatus::Draw(_) => GameResult::Draw,
+            GameStatus::Ongoing { .. } => GameResult::Ongoing,
+        }
+    }
+
+    pub fn to_pgn(&self, tags: &PgnTags) -> String {
+        // returns the game in portable game notation
+        let result = self.result();
+        let mut pgn = String::new();
+
+        // -- TAGS --
+
+        pgn.push_str(&tag_pair("Event", &tags.event));
+        pgn.push_str(&tag_pair("Site", &tags.site));
+        pgn.push_str(&tag_pair("Date", &tags.date));
+        pgn.push_str(&tag_pair("Round", &tags.round));
+        pgn.push_str(&tag_pair("White", &tags.white));
+        pgn.push_str(&tag_pair("Black", &tags.black));
+        pgn.push_str(&tag_pair("Result", result.token()));
+
+        let variant = self.start_position.variant;
+        if variant != Variant::Standard {
+            pgn.push_str(&tag_pair("Variant", variant.name()));
+        } else if self.start_position.chess960 {
+            pgn.push_str(&tag_pair("Variant", "Chess960"));
+        }
+
+        let start_fen = self.start_position.to_fen();
+        if start_fen != variant.start_position().to_fen() {
+            // game didn't start from the standard position
+            pgn.push_str(&tag_pair("SetUp", "1"));
+            pgn.push_str(&tag_pair("FEN", &start_fen));
+        }
+
+        pgn.push('\n');
+
+        // -- MOVE TEXT --
+
+        let mut tokens: Vec<String> = Vec::new();
+        let mut move_number = self.start_position.fullmove_number;
+        let mut colours_turn = self.start_position.colours_turn;
+
+        for (move_count, san_move) in self.san_moves().iter().enumerate() {
+            if colours_turn == PieceColour::White {
+                tokens.push(format!("{}.", move_number));
+            } else if move_count == 0 {
+                tokens.push(format!("{}...", move_number)); // game started with black to move
+            }
+
+            tokens.push(san_move.clone());
+
+            if colours_turn == PieceColour::Black {
+                move_number += 1;
+            }
+            colours_turn = colours_turn.opponent();
+        }
+
+        tokens.push(result.token().to_string());
+
+        let mut line_length = 0;
+        for token in tokens {
+            if line_length != 0 && line_length + 1 + token.len() > LINE_LENGTH {
+                // start a new line when the token doesn't fit
+                pgn.push('\n');
+                line_length = 0;
+            } else if line_length != 0 {
+                pgn.push(' ');
+                line_length += 1;
+            }
+
+            line_length += token.len();
+            pgn.push_str(&token);
+        }
+
+        pgn.push('\n');
+        pgn
+    }
+}
@@ -0,0 +1,86 @@
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum PieceType {
+    // type of piece (None for empty space)
+    Pawn,
+    Rook,
+    Knight,
+    Bishop,
+    Queen,
+    King,
+    None,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum PieceColour {
+    // colour of piece (None for empty space)
+    White,
+    Black,
+    None,
+}
+
+impl PieceColour {
+    pub fn opponent(self) -> PieceColour {
+        // returns the colour of the other side (None stays None)
+        match self {
+            PieceColour::White => PieceColour::Black,
+            PieceColour::Black => PieceColour::White,
+            PieceColour::None => PieceColour::None,
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Piece {
+    // chess piece
+    pub piece_type: PieceType,
+    pub piece_colour: PieceColour,
+}
+
+impl Piece {
+    pub const EMPTY: Piece = Piece {
+        piece_type: PieceType::None,
+        piece_colour: PieceColour::None,
+    }; // empty square
+
+    pub fn from_fen_char(fen_char: char) -> Option<Piece> {
+        // returns the piece a fen character stands for (uppercase = white, lowercase = black)
+        let piece_type = match fen_char.to_ascii_lowercase() {
+            'p' => PieceType::Pawn,   // pawn (p)
+            'r' => PieceType::Rook,   // rook (r)
+            'n' => PieceType::Knight, // knight (n)
+            'b' => PieceType::Bishop, // bishop (b)
+            'q' => PieceType::Queen,  // queen (q)
+            'k' => PieceType::King,   // king (k)
+            _ => return None,         // not piece character (e.g: d)
+        };
+
+        let piece_colour = match fen_char.is_uppercase() {
+            true => PieceColour::White,  // white (uppercase)
+            false => PieceColour::Black, // black (lowercase)
+        };
+
+        Some(Piece {
+            piece_type,
+            piece_colour,
+        })
+    }
+
+    pub fn fen_char(&self) -> Option<char> {
+        // returns the fen character of the piece (None for empty space)
+        let fen_char = match self.piece_type {
+            PieceType::Pawn => 'p',
+            PieceType::Rook => 'r',
+            PieceType::Knight => 'n',
+            PieceType::Bishop => 'b',
+            PieceType::Queen => 'q',
+            PieceType::King => 'k',
+            PieceType::None => return None,
+        };
+
+        match self.piece_colour {
+            PieceColour::White => Some(fen_char.to_ascii_uppercase()), // white
+            PieceColour::Black => Some(fen_char),                      // black
+            PieceColour::None => None,
+        }
+    }
+}
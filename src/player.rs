@@ -0,0 +1,151 @@
+use std::time::{SystemTime, UNIX_EPOCH};
+use std::{fs, io};
+
+use crate::draw::DrawReason;
+use crate::game::Game;
+use crate::position::Move;
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum PlayerAction {
+    // what a player does on their turn
+    Move(Move),
+    Resign,
+    OfferDraw, // the opponent is asked if they accept (the player moves again if they don't)
+    Undo,      // take back the last move
+    Redo,      // play the last move taken back again
+}
+
+pub trait Player {
+    // returns the name to show for the player (e.g: when saying what it played)
+    fn name(&self) -> String;
+
+    // returns what the player does on their turn (one legal move per promotion piece)
+    fn choose_action(&mut self, game: &Game, legal_moves: &[Move]) -> PlayerAction;
+
+    fn accepts_draw(&mut self, _game: &Game) -> bool {
+        // returns true if the player agrees to a draw offered by their opponent
+        false
+    }
+
+    fn claims_draw(&mut self, _game: &Game, _draw_reason: DrawReason) -> bool {
+        // returns true if the player claims a draw they are allowed to (threefold repetition or fifty move rule)
+        false
+    }
+
+    fn is_human(&self) -> bool {
+        // returns true if a person is choosing the moves (undo and redo go back to their turn)
+        false
+    }
+}
+
+// ------- RANDOM PLAYER -------
+
+#[derive(Debug, Clone)]
+pub struct RandomPlayer {
+    // plays any legal move, each as likely as the others
+    state: u64, // random number generator state
+}
+
+impl RandomPlayer {
+    pub fn new() -> RandomPlayer {
+        // returns a random player seeded from the clock
+        let seed = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|time| time.as_nanos() as u64)
+            .unwrap_or(0);
+        RandomPlayer::with_seed(seed)
+    }
+
+    pub fn with_seed(seed: u64) -> RandomPlayer {
+        // returns a random player that always makes the same choices for the same seed
+        RandomPlayer { state: seed }
+    }
+
+    pub(crate) fn next_random(&mut self) -> u64 {
+        // returns the next number from the generator (splitmix64)
+        self.state = self.state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+}
+
+impl Default for RandomPlayer {
+    fn default() -> Self {
+        RandomPlayer::new()
+    }
+}
+
+impl Player for RandomPlayer {
+    fn name(&self) -> String {
+        String::from("Random Mover")
+    }
+
+    fn choose_action(&mut self, _game: &Game, legal_moves: &[Move]) -> PlayerAction {
+        if legal_moves.is_empty() {
+            return PlayerAction::Resign; // shouldn't be asked to move when the game is over
+        }
+
+        let choice = (self.next_random() % legal_moves.len() as u64) as usize;
+        PlayerAction::Move(legal_moves[choice])
+    }
+}
+
+// ------- SCRIPTED PLAYER -------
+
+#[derive(Debug, Clone)]
+pub struct ScriptedPlayer {
+    // plays a list of moves in order (standard or long algebraic), resigning when they run out or one can't be played
+    moves: Vec<String>,
+}
+
+impl ScriptedPlayer {
+    pub fn new(moves: Vec<String>) -> ScriptedPlayer {
+        // returns a player that will make the given moves
+        ScriptedPlayer { moves }
+    }
+
+    pub fn from_file(file_path: &str) -> io::Result<ScriptedPlayer> {
+        // returns a player making the moves in a file (separated by whitespace, move numbers like "12." or "12.Nf3" are skipped)
+        let script = fs::read_to_string(file_path)?;
+        let moves = script
+            .split_whitespace()
+            .map(|word| {
+                let after_number = word.trim_start_matches(|c: char| c.is_ascii_digit());
+                match after_number.starts_with('.') {
+                    true => after_number.trim_start_matches('.'),
+                    false => word,
+                }
+            })
+            .filter(|move_text| !move_text.is_empty())
+            .map(String::from)
+            .collect();
+
+        Ok(ScriptedPlayer::new(moves))
+    }
+}
+
+impl Player for ScriptedPlayer {
+    fn name(&self) -> String {
+        String::from("Script")
+    }
+
+    fn choose_action(&mut self, game: &Game, _legal_moves: &[Move]) -> PlayerAction {
+        // moves this side has made so far (worked out from the game so undo and redo keep the script in step)
+        let plies_played = game.moves().len();
+        let moves_played = match game.start_position.colours_turn == game.position.colours_turn {
+            true => plies_played.div_ceil(2),
+            false => plies_played / 2,
+        };
+
+        let Some(move_text) = self.moves.get(moves_played) else {
+            return PlayerAction::Resign; // script is over
+        };
+
+        match game.position.parse_move(move_text) {
+            Ok(piece_move) => PlayerAction::Move(piece_move),
+            Err(_) => PlayerAction::Resign, // script doesn't fit the game
+        }
+    }
+}
@@ -0,0 +1,538 @@
+use crate::atomic::{self, Explosion};
+use crate::bitboard::{square_bit, Bitboard, Bitboards};
+use crate::crazyhouse::Pockets;
+use crate::movegen;
+use crate::piece::{Piece, PieceColour, PieceType};
+use crate::variant::Variant;
+use crate::zobrist;
+
+pub const START_FEN: &str = "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1"; // initial chess position in fen form
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum MoveFlag {
+    // flags for special moves
+    EnPassant,
+    Castling,
+
+    Promotion, // general promotion flag
+
+    // promotion flags added later when type of promotion is chosen
+    RookPromo,
+    KnightPromo,
+    BishopPromo,
+    QueenPromo,
+    KingPromo, // antichess only
+
+    Drop(PieceType), // piece put on the board from the pocket (crazyhouse), start and end are the square it lands on
+    None,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Move {
+    pub start: i32,
+    pub end: i32,
+    pub flag: MoveFlag,
+}
+
+impl Move {
+    pub fn to_long_algebraic(&self) -> String {
+        // returns move as start and end squares with promotion letter (e.g: e2e4, e7e8q), or the piece and square of a drop (e.g: N@f3)
+        if let MoveFlag::Drop(piece_type) = self.flag {
+            let piece = Piece {
+                piece_type,
+                piece_colour: PieceColour::White, // uppercase letter whoever drops it
+            };
+            return format!(
+                "{}@{}",
+                piece.fen_char().unwrap_or('?'),
+                square_name(self.end)
+            );
+        }
+
+        let promotion_part = match self.flag {
+            MoveFlag::RookPromo => "r",
+            MoveFlag::KnightPromo => "n",
+            MoveFlag::BishopPromo => "b",
+            MoveFlag::QueenPromo => "q",
+            MoveFlag::KingPromo => "k",
+            _ => "",
+        };
+
+        format!(
+            "{}{}{}",
+            square_name(self.start),
+            square_name(self.end),
+            promotion_part
+        )
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct UndoInfo {
+    // what make_move can't work out backwards (returned by make_move, used by unmake_move)
+    pub piece_move: Move,
+    pub captured_piece: Piece, // piece on the end square before the move (empty for en passant)
+    pub castling_rights: (bool, bool, bool, bool),
+    pub en_passant_move: i32,
+    pub king_indexes: (i32, i32),
+    pub halfmove_clock: u32,
+    pub checks_given: (u32, u32),
+    pub pockets: Pockets,
+    pub promoted: Bitboard,
+    pub explosion: Explosion, // pieces blown up by the move (atomic)
+    pub hash: u64,
+}
+
+pub fn square_name(index: i32) -> String {
+    // returns algebraic notation of a square (e.g: 52 -> e2)
+    let letter_part = (b'a' + (index % 8) as u8) as char;
+    let number_part = 8 - (index / 8);
+
+    format!("{}{}", letter_part, number_part)
+}
+
+pub fn square_index(square: &str) -> Option<i32> {
+    // returns index of a square given in algebraic notation (e.g: e2 -> 52)
+    let square_chars: Vec<char> = square.chars().collect();
+
+    match square_chars[..] {
+        [letter_part @ 'a'..='h', number_part @ '1'..='8'] => {
+            Some((letter_part as i32 - 'a' as i32) + (8 - (number_part as i32 - '0' as i32)) * 8)
+        }
+        _ => None,
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Position {
+    // full state of a game at one point in time
+    pub board: Vec<Piece>,         // 64 squares (a8 = 0, h1 = 63)
+    pub colours_turn: PieceColour, // side to move
+    // white king side, white queen side, black king side, black queen side
+    pub castling_rights: (bool, bool, bool, bool),
+    pub castling_rooks: (i32, i32, i32, i32), // squares the castling rooks start on (same order)
+    pub chess960: bool,                       // castling is written as king takes rook (e.g: e1h1)
+    pub en_passant_move: i32,                 // en passant target square (-1 if none)
+    pub king_indexes: (i32, i32), // indexes of the white and black kings (-1 if missing, the last one found if there are several in antichess)
+    pub halfmove_clock: u32, // moves since the last capture or pawn move (for the fifty move rule)
+    pub fullmove_number: u32, // starts at 1 and goes up after each black move
+    pub hash: u64,           // zobrist key of the position (see Position::compute_hash)
+    pub bitboards: Bitboards, // the board again as bitboards (kept in step by make_move)
+    pub variant: Variant,    // rules the position is played by
+    pub checks_given: (u32, u32), // checks given by white and black (counted in three-check)
+    pub pockets: Pockets,    // pieces each side can drop (crazyhouse)
+    pub promoted: Bitboard, // squares of pieces that were pawns, they go back to being pawns when taken (crazyhouse)
+}
+
+impl Default for Position {
+    fn default() -> Self {
+        Position::start()
+    }
+}
+
+impl Position {
+    pub fn start() -> Position {
+        // returns the standard starting position
+        Position::from_fen(START_FEN).expect("\x1b[41m--INVALID START FEN--\x1b[0m")
+    }
+
+    pub fn find_kings(board: &[Piece]) -> (i32, i32) {
+        // returns indexes of the kings (-1 if a king is missing)
+        let mut king_indexes: (i32, i32) = (-1, -1);
+
+        for (index, piece) in board.iter().enumerate() {
+            // looping over every piece to find the kings
+            if piece.piece_type == PieceType::King {
+                match piece.piece_colour {
+                    PieceColour::White => king_indexes.0 = index as i32, // white king
+                    PieceColour::Black => king_indexes.1 = index as i32, // black king
+                    PieceColour::None => (), // somthing has gone wrong if this happens ;-;
+                }
+            }
+        }
+
+        king_indexes
+    }
+
+    pub fn castling_rook(&self, colour: PieceColour, king_side: bool) -> i32 {
+        // returns the square the rook castling on the given side starts on
+        match (colour, king_side) {
+            (PieceColour::White, true) => self.castling_rooks.0,
+            (PieceColour::White, false) => self.castling_rooks.1,
+            (_, true) => self.castling_rooks.2,
+            (_, false) => self.castling_rooks.3,
+        }
+    }
+
+    pub fn legal_moves_from(&self, index: i32) -> Vec<Move> {
+        // returns legal moves of the piece on the given square
+        if !(0..64).contains(&index) || self.board[index as usize].piece_colour != self.colours_turn
+        {
+            return Vec::new();
+        }
+
+        movegen::legal_move_gen(self, square_bit(index))
+    }
+
+    pub fn legal_moves(&self) -> Vec<Move> {
+        // returns all legal moves that the player to move can make
+        let mut legal_moves = movegen::legal_move_gen(self, !0);
+        if self.variant == Variant::Crazyhouse {
+            legal_moves.extend(movegen::drop_move_gen(self));
+        }
+        legal_moves
+    }
+
+    pub fn legal_moves_with_promotions(&self) -> Vec<Move> {
+        // returns all legal moves with each promotion split into one move per piece it can promote to
+        let mut legal_moves: Vec<Move> = Vec::new();
+        let promotion_flags: &[MoveFlag] = match self.variant {
+            Variant::Antichess => &[
+                MoveFlag::QueenPromo,
+                MoveFlag::RookPromo,
+                MoveFlag::BishopPromo,
+                MoveFlag::KnightPromo,
+                MoveFlag::KingPromo,
+            ],
+            _ => &[
+                MoveFlag::QueenPromo,
+                MoveFlag::RookPromo,
+                MoveFlag::BishopPromo,
+                MoveFlag::KnightPromo,
+            ],
+        };
+
+        for legal_move in self.legal_moves() {
+            if legal_move.flag == MoveFlag::Promotion {
+                for &flag in promotion_flags {
+                    legal_moves.push(Move { flag, ..legal_move });
+                }
+            } else {
+                legal_moves.push(legal_move);
+            }
+        }
+
+        legal_moves
+    }
+
+    pub fn make_move(&mut self, piece_move: Move) -> UndoInfo {
+        // applies a move to the position and passes the turn to the other player
+        let start_piece = match piece_move.flag {
+            MoveFlag::Drop(piece_type) => Piece {
+                piece_type,
+                piece_colour: self.colours_turn,
+            },
+            _ => self.board[piece_move.start as usize],
+        };
+        let capture_piece = match piece_move.flag {
+            MoveFlag::Castling => Piece::EMPTY, // end square can be the king's own rook
+            _ => self.board[piece_move.end as usize],
+        };
+        let mut new_en_passant = -1;
+        let explodes =
+            self.variant == Variant::Atomic && atomic::is_atomic_capture(self, piece_move);
+
+        let mut undo_info = UndoInfo {
+            piece_move,
+            captured_piece: capture_piece,
+            castling_rights: self.castling_rights,
+            en_passant_move: self.en_passant_move,
+            king_indexes: self.king_indexes,
+            halfmove_clock: self.halfmove_clock,
+            checks_given: self.checks_given,
+            pockets: self.pockets,
+            promoted: self.promoted,
+            explosion: [Piece::EMPTY; 9],
+            hash: self.hash,
+        };
+
+        if self.variant == Variant::Crazyhouse {
+            self.crazyhouse_move(piece_move, capture_piece); // taken piece goes in the pocket, dropped piece comes out
+        }
+
+        // take the old side to move, castling rights and en passant out of the hash (added back at the end)
+        self.hash ^= zobrist::side_key(self.colours_turn)
+            ^ zobrist::castling_key(self.castling_rights)
+            ^ zobrist::en_passant_key(self);
+
+        let mut end_index = piece_move.end; // where the moved piece ends up
+        if piece_move.flag == MoveFlag::Castling {
+            end_index = self.castle(piece_move, start_piece.piece_colour); // moves the rook too
+        } else if let MoveFlag::Drop(_) = piece_move.flag {
+            self.set_piece(piece_move.end, start_piece); // put the piece on its (empty) square
+        } else {
+            self.set_piece(piece_move.end, start_piece); // move piece to new square
+            self.set_piece(piece_move.start, Piece::EMPTY); // remove piece at old position
+        }
+
+        let mut pawn_dir = -1; // direction of pawn travel
+        let mut pawn_start = 6; // start rank of pawn
+        if start_piece.piece_colour == PieceColour::Black {
+            pawn_dir = 1;
+            pawn_start = 1;
+        }; // black pawn values
+
+        // -- en passant stuff --
+
+        if piece_move.flag == MoveFlag::EnPassant {
+            // remove piece that is taken by en passant
+            self.set_piece(self.en_passant_move - (8 * pawn_dir), Piece::EMPTY);
+        }
+
+        if start_piece.piece_type == PieceType::Pawn
+            && piece_move.start / 8 == pawn_start
+            && piece_move.start + (16 * pawn_dir) == piece_move.end
+        {
+            // make this pos next en passant move if its a double pawn push
+            new_en_passant = piece_move.end - (8 * pawn_dir);
+        }
+
+        // -- promotion stuff --
+
+        let promotion_type = match piece_move.flag {
+            MoveFlag::RookPromo => PieceType::Rook, // change pawn to rook
+            MoveFlag::KnightPromo => PieceType::Knight, // change pawn to knight
+            MoveFlag::BishopPromo => PieceType::Bishop, // change pawn to bishop
+            MoveFlag::QueenPromo => PieceType::Queen, // change pawn to queen
+            MoveFlag::KingPromo => PieceType::King, // change pawn to king (antichess)
+            _ => PieceType::None,
+        };
+
+        if promotion_type != PieceType::None {
+            self.set_piece(
+                piece_move.end,
+                Piece {
+                    piece_type: promotion_type,
+                    piece_colour: start_piece.piece_colour,
+                },
+            )
+        }
+
+        // -- king index changes --
+
+        if start_piece.piece_type == PieceType::King || promotion_type == PieceType::King {
+            match start_piece.piece_colour {
+                PieceColour::White => self.king_indexes.0 = end_index,
+                PieceColour::Black => self.king_indexes.1 = end_index,
+                PieceColour::None => (),
+            }
+        }
+        if capture_piece.piece_type == PieceType::King {
+            // kings can be taken in antichess
+            match capture_piece.piece_colour {
+                PieceColour::White => self.king_indexes.0 = -1,
+                _ => self.king_indexes.1 = -1,
+            }
+        }
+
+        // -- castling stuff --
+
+        // remove castling rights if rook/king is moving
+        match start_piece.piece_type {
+            PieceType::King => {
+                // remove all castling rights for that colour
+                match start_piece.piece_colour {
+                    PieceColour::White => {
+                        self.castling_rights.0 = false;
+                        self.castling_rights.1 = false;
+                    }
+                    PieceColour::Black => {
+                        self.castling_rights.2 = false;
+                        self.castling_rights.3 = false;
+                    }
+                    _ => (),
+                }
+            }
+            PieceType::Rook => {
+                // remove castling rights for that side
+                remove_rook_castling_rights(
+                    &mut self.castling_rights,
+                    self.castling_rooks,
+                    piece_move.start,
+                    start_piece.piece_colour,
+                )
+            }
+            _ => (),
+        }
+        // remove castling rights if rook is captured
+        if capture_piece.piece_type == PieceType::Rook {
+            remove_rook_castling_rights(
+                &mut self.castling_rights,
+                self.castling_rooks,
+                piece_move.end,
+                capture_piece.piece_colour,
+            )
+        }
+
+        // -- atomic explosion --
+
+        if explodes {
+            undo_info.explosion = self.explode(piece_move.end); // also takes away castling rights of blown up kings and rooks
+        }
+
+        // -- move counters --
+
+        if start_piece.piece_type == PieceType::Pawn
+            || capture_piece.piece_type != PieceType::None
+            || piece_move.flag == MoveFlag::EnPassant
+        {
+            self.halfmove_clock = 0; // pawn moves and captures reset the fifty move count
+        } else {
+            self.halfmove_clock += 1;
+        }
+
+        if start_piece.piece_colour == PieceColour::Black {
+            self.fullmove_number += 1;
+        }
+
+        self.en_passant_move = new_en_passant;
+        self.colours_turn = self.colours_turn.opponent(); // swap whos turn it is
+
+        if self.variant == Variant::ThreeCheck && self.is_in_check() {
+            self.hash ^= zobrist::checks_key(self.checks_given);
+            match start_piece.piece_colour {
+                PieceColour::White => self.checks_given.0 += 1,
+                _ => self.checks_given.1 += 1,
+            }
+            self.hash ^= zobrist::checks_key(self.checks_given);
+        }
+
+        // add the new side to move, castling rights and en passant into the hash
+        self.hash ^= zobrist::side_key(self.colours_turn)
+            ^ zobrist::castling_key(self.castling_rights)
+            ^ zobrist::en_passant_key(self);
+
+        undo_info
+    }
+
+    pub fn unmake_move(&mut self, undo_info: UndoInfo) {
+        // takes back the move make_move returned the undo info for, restoring the exact previous position
+        let piece_move = undo_info.piece_move;
+
+        self.colours_turn = self.colours_turn.opponent(); // give the turn back
+        if self.colours_turn == PieceColour::Black {
+            self.fullmove_number -= 1;
+        }
+
+        if piece_move.flag == MoveFlag::Castling {
+            self.uncastle(piece_move, self.colours_turn);
+        } else if let MoveFlag::Drop(_) = piece_move.flag {
+            self.set_piece(piece_move.end, Piece::EMPTY); // dropped piece goes back in the pocket (below)
+        } else {
+            if self.variant == Variant::Atomic {
+                self.unexplode(piece_move.end, undo_info.explosion); // capturing piece comes back before it moves back
+            }
+            self.unmove_piece(undo_info);
+        }
+
+        self.castling_rights = undo_info.castling_rights;
+        self.en_passant_move = undo_info.en_passant_move;
+        self.king_indexes = undo_info.king_indexes;
+        self.halfmove_clock = undo_info.halfmove_clock;
+        self.checks_given = undo_info.checks_given;
+        self.pockets = undo_info.pockets;
+        self.promoted = undo_info.promoted;
+        self.hash = undo_info.hash;
+    }
+
+    fn unmove_piece(&mut self, undo_info: UndoInfo) {
+        // puts the piece moved by anything but castling back, along with whatever it took
+        let piece_move = undo_info.piece_move;
+        let mut moved_piece = self.board[piece_move.end as usize];
+
+        if matches!(
+            piece_move.flag,
+            MoveFlag::RookPromo
+                | MoveFlag::KnightPromo
+                | MoveFlag::BishopPromo
+                | MoveFlag::QueenPromo
+                | MoveFlag::KingPromo
+        ) {
+            moved_piece.piece_type = PieceType::Pawn; // promoted piece goes back to being a pawn
+        }
+
+        self.set_piece(piece_move.start, moved_piece); // move piece back to old square
+        self.set_piece(piece_move.end, undo_info.captured_piece); // put back anything taken
+
+        if piece_move.flag == MoveFlag::EnPassant {
+            // put back pawn taken by en passant
+            let taken_index = match moved_piece.piece_colour {
+                PieceColour::White => piece_move.end + 8,
+                _ => piece_move.end - 8,
+            };
+            self.set_piece(
+                taken_index,
+                Piece {
+                    piece_type: PieceType::Pawn,
+                    piece_colour: moved_piece.piece_colour.opponent(),
+                },
+            );
+        }
+    }
+
+    fn castle(&mut self, piece_move: Move, colour: PieceColour) -> i32 {
+        // moves the king and rook of a castling move, returns the square the king ends up on
+        let king_side = piece_move.end > piece_move.start;
+        let rook_index = self.castling_rook(colour, king_side);
+        let (king_end, rook_end) = castling_ends(piece_move.start, king_side);
+        let king = self.board[piece_move.start as usize];
+        let rook = self.board[rook_index as usize];
+
+        // both are taken off first as either can land where the other started
+        self.set_piece(piece_move.start, Piece::EMPTY);
+        self.set_piece(rook_index, Piece::EMPTY);
+        self.set_piece(king_end, king);
+        self.set_piece(rook_end, rook);
+
+        king_end
+    }
+
+    fn uncastle(&mut self, piece_move: Move, colour: PieceColour) {
+        // puts the king and rook of a castling move back where they started
+        let king_side = piece_move.end > piece_move.start;
+        let rook_index = self.castling_rook(colour, king_side);
+        let (king_end, rook_end) = castling_ends(piece_move.start, king_side);
+        let king = self.board[king_end as usize];
+        let rook = self.board[rook_end as usize];
+
+        self.set_piece(king_end, Piece::EMPTY);
+        self.set_piece(rook_end, Piece::EMPTY);
+        self.set_piece(piece_move.start, king);
+        self.set_piece(rook_index, rook);
+    }
+
+    pub(crate) fn set_piece(&mut self, index: i32, piece: Piece) {
+        // puts a piece (or empty space) on a square, keeping the hash and bitboards up to date
+        let old_piece = self.board[index as usize];
+
+        self.hash ^= zobrist::piece_key(old_piece, index) ^ zobrist::piece_key(piece, index);
+        self.bitboards.toggle(index, old_piece);
+        self.bitboards.toggle(index, piece);
+        self.board[index as usize] = piece;
+    }
+}
+
+pub(crate) fn castling_ends(king_index: i32, king_side: bool) -> (i32, i32) {
+    // returns where the king and rook land when castling (g and f files king side, c and d files queen side)
+    let back_rank_start = (king_index / 8) * 8;
+    match king_side {
+        true => (back_rank_start + 6, back_rank_start + 5),
+        false => (back_rank_start + 2, back_rank_start + 3),
+    }
+}
+
+pub(crate) fn remove_rook_castling_rights(
+    castling_rights: &mut (bool, bool, bool, bool),
+    castling_rooks: (i32, i32, i32, i32),
+    rook_index: i32,
+    rook_colour: PieceColour,
+) {
+    // removes the castling right belonging to a rook on its starting square
+    match rook_colour {
+        PieceColour::White if rook_index == castling_rooks.0 => castling_rights.0 = false, // white king side
+        PieceColour::White if rook_index == castling_rooks.1 => castling_rights.1 = false, // white queen side
+        PieceColour::Black if rook_index == castling_rooks.2 => castling_rights.2 = false, // black king side
+        PieceColour::Black if rook_index == castling_rooks.3 => castling_rights.3 = false, // black queen side
+        _ => (),
+    }
+}
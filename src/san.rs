@@ -0,0 +1,353 @@
+use std::{error, fmt};
+
+use crate::piece::PieceType;
+use crate::position::{square_index, square_name, Move, MoveFlag, Position};
+use crate::status::GameStatus;
+
+fn piece_letter(piece_type: PieceType) -> &'static str {
+    // returns letter used for a piece in algebraic notation (pawns have none)
+    match piece_type {
+        PieceType::Rook => "R",
+        PieceType::Knight => "N",
+        PieceType::Bishop => "B",
+        PieceType::Queen => "Q",
+        PieceType::King => "K",
+        _ => "",
+    }
+}
+
+impl Position {
+    pub fn move_to_san(&self, piece_move: Move) -> String {
+        // returns standard algebraic notation of a legal move in this position (e.g: Nf3, exd5, e8=Q+, O-O, N@f3)
+        let piece = self.board[piece_move.start as usize];
+        let mut san = String::new();
+
+        if piece_move.flag == MoveFlag::Castling {
+            // castling
+            match piece_move.end > piece_move.start {
+                true => san.push_str("O-O"),    // king side
+                false => san.push_str("O-O-O"), // queen side
+            }
+        } else if let MoveFlag::Drop(_) = piece_move.flag {
+            san.push_str(&piece_move.to_long_algebraic()); // drops look the same either way (e.g: N@f3, P@e4)
+        } else {
+            let is_capture = self.board[piece_move.end as usize].piece_type != PieceType::None
+                || piece_move.flag == MoveFlag::EnPassant;
+
+            if piece.piece_type == PieceType::Pawn {
+                if is_capture {
+                    san.push_str(&square_name(piece_move.start)[..1]); // file the pawn took from
+                }
+            } else {
+                san.push_str(piece_letter(piece.piece_type));
+                san.push_str(&self.disambiguation(piece_move));
+            }
+
+            if is_capture {
+                san.push('x');
+            }
+
+            san.push_str(&square_name(piece_move.end));
+
+            let promotion_type = match piece_move.flag {
+                MoveFlag::RookPromo => PieceType::Rook,
+                MoveFlag::KnightPromo => PieceType::Knight,
+                MoveFlag::BishopPromo => PieceType::Bishop,
+                MoveFlag::QueenPromo => PieceType::Queen,
+                MoveFlag::KingPromo => PieceType::King,
+                _ => PieceType::None,
+            };
+
+            if promotion_type != PieceType::None {
+                san.push('=');
+                san.push_str(piece_letter(promotion_type));
+            }
+        }
+
+        // -- check and checkmate --
+
+        let mut position_after_move = self.clone();
+        position_after_move.make_move(piece_move);
+
+        if position_after_move.is_in_check() {
+            match position_after_move.status() {
+                GameStatus::Checkmate(_) => san.push('#'),
+                _ => san.push('+'),
+            }
+        }
+
+        san
+    }
+
+    fn disambiguation(&self, piece_move: Move) -> String {
+        // returns file, rank or square of the start square when another piece of the same type could move to the same square
+        let piece = self.board[piece_move.start as usize];
+        let start_name = square_name(piece_move.start);
+
+        let rivals: Vec<Move> = self
+            .legal_moves()
+            .into_iter()
+            .filter(|other_move| {
+                other_move.end == piece_move.end
+                    && other_move.start != piece_move.start
+                    && self.board[other_move.start as usize] == piece
+            })
+            .collect();
+
+        if rivals.is_empty() {
+            return String::new();
+        }
+
+        if rivals
+            .iter()
+            .all(|other_move| other_move.start % 8 != piece_move.start % 8)
+        {
+            return start_name[..1].to_string(); // file is enough
+        }
+
+        if rivals
+            .iter()
+            .all(|other_move| other_move.start / 8 != piece_move.start / 8)
+        {
+            return start_name[1..].to_string(); // rank is enough
+        }
+
+        start_name // needs the whole square
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MoveParseError {
+    // reasons a typed move can't be played
+    BadNotation,                // not standard or long algebraic notation (or a drop)
+    IllegalMove,                // no legal move matches
+    PromotionNeeded,            // pawn reaches the last rank but no piece was given
+    AmbiguousMove(Vec<String>), // more than one legal move matches (as SAN)
+}
+
+impl fmt::Display for MoveParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            MoveParseError::BadNotation => {
+                write!(
+                    f,
+                    "not a move in algebraic notation (e.g: Nf3, exd5, O-O, e7e8q, N@f3)"
+                )
+            }
+            MoveParseError::IllegalMove => write!(f, "no legal move matches"),
+            MoveParseError::PromotionNeeded => {
+                write!(f, "say which piece to promote to (e.g: e8=Q)")
+            }
+            MoveParseError::AmbiguousMove(candidates) => {
+                write!(f, "could be any of {}", candidates.join(", "))
+            }
+        }
+    }
+}
+
+impl error::Error for MoveParseError {}
+
+#[derive(Debug, Default)]
+struct MovePattern {
+    // parts of a typed move (None where the notation leaves them out)
+    piece_type: Option<PieceType>,
+    start_file: Option<i32>,
+    start_rank: Option<i32>, // row on the board (0 = rank 8)
+    end: i32,
+    promotion: Option<MoveFlag>,
+    castling_side: Option<i32>, // 1 = king side, -1 = queen side
+    drop: Option<PieceType>,    // piece put down from the pocket (crazyhouse)
+}
+
+fn promotion_flag(promotion_char: char) -> Option<MoveFlag> {
+    // returns promotion flag for a piece letter (either case)
+    match promotion_char.to_ascii_lowercase() {
+        'q' => Some(MoveFlag::QueenPromo),
+        'r' => Some(MoveFlag::RookPromo),
+        'b' => Some(MoveFlag::BishopPromo),
+        'n' => Some(MoveFlag::KnightPromo),
+        'k' => Some(MoveFlag::KingPromo),
+        _ => None,
+    }
+}
+
+fn parse_pattern(text: &str) -> Option<MovePattern> {
+    // splits typed move into its parts (None if it isn't in a notation we know)
+    let text = text.trim().trim_end_matches(['+', '#', '!', '?']);
+
+    // -- castling --
+
+    match text {
+        "O-O" | "0-0" | "o-o" => {
+            return Some(MovePattern {
+                castling_side: Some(1),
+                ..MovePattern::default()
+            })
+        }
+        "O-O-O" | "0-0-0" | "o-o-o" => {
+            return Some(MovePattern {
+                castling_side: Some(-1),
+                ..MovePattern::default()
+            })
+        }
+        _ => (),
+    }
+
+    // -- drops (e.g: N@f3, P@e4 or just @e4) --
+
+    if let Some((piece_part, square_part)) = text.split_once('@') {
+        let piece_type = match piece_part.to_uppercase().as_str() {
+            "" | "P" => PieceType::Pawn,
+            "N" => PieceType::Knight,
+            "B" => PieceType::Bishop,
+            "R" => PieceType::Rook,
+            "Q" => PieceType::Queen,
+            _ => return None,
+        };
+
+        return Some(MovePattern {
+            end: square_index(square_part)?,
+            drop: Some(piece_type),
+            ..MovePattern::default()
+        });
+    }
+
+    let chars: Vec<char> = text.chars().filter(|&c| c != '-').collect();
+
+    // -- long algebraic (e.g: e2e4, e7e8q) --
+
+    if chars.len() >= 4 {
+        let start = square_index(&chars[0..2].iter().collect::<String>());
+        let end = square_index(&chars[2..4].iter().collect::<String>());
+
+        if let (Some(start), Some(end)) = (start, end) {
+            let promotion = match chars[4..] {
+                [] => None,
+                [promotion_char] | ['=', promotion_char] => Some(promotion_flag(promotion_char)?),
+                _ => return None,
+            };
+
+            return Some(MovePattern {
+                start_file: Some(start % 8),
+                start_rank: Some(start / 8),
+                end,
+                promotion,
+                ..MovePattern::default()
+            });
+        }
+    }
+
+    // -- standard algebraic (e.g: Nf3, exd5, Raxd1, e8=Q) --
+
+    let mut rest = &chars[..];
+
+    let piece_type = match rest.first() {
+        Some('K') => Some(PieceType::King),
+        Some('Q') => Some(PieceType::Queen),
+        Some('R') => Some(PieceType::Rook),
+        Some('B') => Some(PieceType::Bishop),
+        Some('N') => Some(PieceType::Knight),
+        _ => None,
+    };
+    if piece_type.is_some() {
+        rest = &rest[1..];
+    }
+
+    // promotion on the end (e.g: =Q or just Q, any case after '=' since it can't be a file there)
+    let mut promotion = None;
+    if let [before @ .., last] = rest {
+        let after_equals = before.last() == Some(&'=');
+        if let Some(flag) =
+            promotion_flag(*last).filter(|_| after_equals || last.is_ascii_uppercase())
+        {
+            promotion = Some(flag);
+            rest = before.strip_suffix(&['=']).unwrap_or(before);
+        }
+    }
+
+    // destination square is the last two characters
+    if rest.len() < 2 {
+        return None;
+    }
+    let end = square_index(&rest[rest.len() - 2..].iter().collect::<String>())?;
+    rest = &rest[..rest.len() - 2];
+    rest = rest.strip_suffix(&['x']).unwrap_or(rest); // capture mark
+
+    // anything left is the start file and/or rank
+    let mut start_file = None;
+    let mut start_rank = None;
+    for &disambiguation_char in rest {
+        match disambiguation_char {
+            'a'..='h' if start_file.is_none() => {
+                start_file = Some(disambiguation_char as i32 - 'a' as i32)
+            }
+            '1'..='8' if start_rank.is_none() => {
+                start_rank = Some(8 - (disambiguation_char as i32 - '0' as i32))
+            }
+            _ => return None,
+        }
+    }
+
+    Some(MovePattern {
+        piece_type: Some(piece_type.unwrap_or(PieceType::Pawn)),
+        start_file,
+        start_rank,
+        end,
+        promotion,
+        castling_side: None,
+        drop: None,
+    })
+}
+
+impl Position {
+    pub fn parse_move(&self, text: &str) -> Result<Move, MoveParseError> {
+        // returns the legal move typed in standard or long algebraic notation (e.g: Nf3, exd5, O-O, e8=Q, e7e8q, N@f3)
+        let pattern = parse_pattern(text).ok_or(MoveParseError::BadNotation)?;
+
+        let candidates: Vec<Move> = self
+            .legal_moves_with_promotions()
+            .into_iter()
+            .filter(|legal_move| match (pattern.castling_side, pattern.drop) {
+                (Some(side), _) => {
+                    legal_move.flag == MoveFlag::Castling
+                        && (legal_move.end - legal_move.start).signum() == side
+                }
+                (None, Some(piece_type)) => {
+                    legal_move.flag == MoveFlag::Drop(piece_type) && legal_move.end == pattern.end
+                }
+                (None, None) => {
+                    !matches!(legal_move.flag, MoveFlag::Drop(_))
+                        && legal_move.end == pattern.end
+                        && pattern.piece_type.is_none_or(|piece_type| {
+                            self.board[legal_move.start as usize].piece_type == piece_type
+                        })
+                        && pattern
+                            .start_file
+                            .is_none_or(|file| legal_move.start % 8 == file)
+                        && pattern
+                            .start_rank
+                            .is_none_or(|rank| legal_move.start / 8 == rank)
+                        && pattern.promotion.is_none_or(|flag| legal_move.flag == flag)
+                }
+            })
+            .collect();
+
+        match candidates[..] {
+            [] => Err(MoveParseError::IllegalMove),
+            [legal_move] => Ok(legal_move),
+            _ if pattern.promotion.is_none()
+                && candidates.iter().all(|legal_move| {
+                    legal_move.start == candidates[0].start && legal_move.end == candidates[0].end
+                }) =>
+            {
+                Err(MoveParseError::PromotionNeeded) // same move with each promotion piece
+            }
+            _ => Err(MoveParseError::AmbiguousMove(
+                candidates
+                    .iter()
+                    .map(|legal_move| self.move_to_san(*legal_move))
+                    .collect(),
+            )),
+        }
+    }
+}
@@ -0,0 +1,447 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, Instant};
+
+use crate::game::Game;
+use crate::piece::{PieceColour, PieceType};
+use crate::position::{Move, MoveFlag, Position};
+use crate::variant::VariantOutcome;
+
+pub const MATE_SCORE: i32 = 30_000; // score for giving mate right now (mates further away score a little less)
+const MAX_PLY: usize = 64; // deepest the search will go (including captures at the end of lines)
+const MOVE_OVERHEAD: Duration = Duration::from_millis(50); // time kept back for the gui and communication
+const DEFAULT_MOVES_TO_GO: u64 = 30; // moves the remaining time is shared between when nobody says
+const CHECK_EVERY: u64 = 1024; // nodes between looking at the clock and the stop flag
+const KING_ATTACK_BONUS: i32 = 8; // centipawns per attack around a king at full aggressiveness
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct SearchLimits {
+    // how long to search for (None when there is no limit of that kind)
+    pub depth: Option<u32>,
+    pub nodes: Option<u64>,
+    pub move_time: Option<Duration>,
+    pub time_left: (Option<Duration>, Option<Duration>), // (white, black)
+    pub increment: (Duration, Duration),                 // (white, black)
+    pub moves_to_go: Option<u64>,
+    pub infinite: bool, // search until told to stop
+}
+
+impl SearchLimits {
+    pub fn time_budget(&self, colour: PieceColour) -> Option<Duration> {
+        // returns how long the side to move should think for (None for no time limit)
+        if self.infinite {
+            return None;
+        }
+        if let Some(move_time) = self.move_time {
+            return Some(move_time);
+        }
+
+        let (time_left, increment) = match colour {
+            PieceColour::White => (self.time_left.0?, self.increment.0),
+            _ => (self.time_left.1?, self.increment.1),
+        };
+
+        let moves_to_go = self.moves_to_go.unwrap_or(DEFAULT_MOVES_TO_GO).max(1) as u32;
+        let budget = time_left / moves_to_go + increment * 3 / 4;
+        let most_allowed = time_left.saturating_sub(MOVE_OVERHEAD); // never run the clock out
+
+        Some(budget.min(most_allowed))
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct SearchStyle {
+    // how the searching side likes to play (the default is neutral)
+    pub contempt: i32, // centipawns a draw is worse than an equal position for the searching side (negative to want draws)
+    pub aggressiveness: i32, // -100 to 100, above 0 attacks the other king, below 0 guards its own
+}
+
+impl SearchStyle {
+    fn bonus(&self, position: &Position, colour: PieceColour) -> i32 {
+        // returns the extra score the style gives the position for the given colour (the searching side)
+        match self.aggressiveness {
+            0 => 0,
+            aggressiveness if aggressiveness > 0 => {
+                position.king_pressure(colour) * KING_ATTACK_BONUS * aggressiveness / 100
+            }
+            aggressiveness => {
+                position.king_pressure(colour.opponent()) * KING_ATTACK_BONUS * aggressiveness / 100
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct SearchResult {
+    // what the deepest finished search found
+    pub best_move: Option<Move>, // None if there are no legal moves
+    pub score: i32,              // centipawns for the side to move (see mate_in)
+    pub depth: u32,
+    pub nodes: u64,
+    pub time: Duration,
+    pub principal_variation: Vec<Move>, // best line of play for both sides, starting with best_move
+}
+
+impl SearchResult {
+    pub fn mate_in(&self) -> Option<i32> {
+        // returns moves until mate if the score is a forced mate (negative when the side to move gets mated)
+        if self.score.abs() < MATE_SCORE - MAX_PLY as i32 {
+            return None;
+        }
+
+        let plies = MATE_SCORE - self.score.abs();
+        let moves = (plies + 1) / 2;
+        Some(match self.score > 0 {
+            true => moves,
+            false => -moves,
+        })
+    }
+}
+
+fn is_capture(position: &Position, piece_move: Move) -> bool {
+    // returns true if the move takes a piece
+    position.captured_piece(piece_move) != PieceType::None
+}
+
+fn move_order_score(position: &Position, piece_move: Move, pv_move: Option<Move>) -> i32 {
+    // returns how early a move should be searched (best line so far first, then captures of big pieces by small ones)
+    if pv_move == Some(piece_move) {
+        return i32::MAX;
+    }
+
+    let mut score = position.mvv_lva_score(piece_move);
+    if score != 0 {
+        score += 10_000; // captures before quiet moves
+    }
+    if piece_move.flag == MoveFlag::QueenPromo {
+        score += 9_000;
+    }
+
+    score
+}
+
+struct Searcher<'a> {
+    // state shared by every node of one search
+    stop: &'a AtomicBool,
+    root_colour: PieceColour, // side the search is for (the style is theirs)
+    style: SearchStyle,
+    deadline: Option<Instant>,
+    node_limit: Option<u64>,
+    finish_depth: bool, // keep going whatever the stop flag or node limit says (depth 1 of depth and node limited searches)
+    nodes: u64,
+    stopped: bool, // ran out of time or was told to stop (results of this depth can't be trusted)
+    keys: Vec<u64>, // hashes of the positions leading to the current one (for repetitions)
+    pv_table: Vec<Vec<Move>>, // best line found from each ply in the current node
+    previous_pv: Vec<Move>, // best line from the last depth searched (searched first)
+}
+
+impl Searcher<'_> {
+    fn should_stop(&mut self) -> bool {
+        // returns true if the search has to end now
+        if !self.stopped && self.nodes.is_multiple_of(CHECK_EVERY) {
+            let told_to_stop = self.stop.load(Ordering::Relaxed)
+                || self
+                    .node_limit
+                    .is_some_and(|node_limit| self.nodes >= node_limit);
+            self.stopped = (told_to_stop && !self.finish_depth)
+                || self
+                    .deadline
+                    .is_some_and(|deadline| Instant::now() >= deadline);
+        }
+        self.stopped
+    }
+
+    fn is_draw(&self, position: &Position) -> bool {
+        // returns true if the position is drawn no matter what is played (repetitions count after one repeat)
+        position.halfmove_clock >= 100
+            || position.is_insufficient_material()
+            || self.keys[..self.keys.len() - 1]
+                .iter()
+                .rev()
+                .take(position.halfmove_clock as usize) // captures and pawn moves can't be undone
+                .any(|&key| key == position.hash)
+    }
+
+    fn draw_score(&self, position: &Position) -> i32 {
+        // returns the score of a drawn position for the side to move
+        match position.colours_turn == self.root_colour {
+            true => -self.style.contempt,
+            false => self.style.contempt,
+        }
+    }
+
+    fn outcome_score(&self, position: &Position, outcome: VariantOutcome, ply: usize) -> i32 {
+        // returns the score of a game ended by the variant's rules for the side to move (scored like mate)
+        match outcome {
+            VariantOutcome::Win(winner) if winner == position.colours_turn => {
+                MATE_SCORE - ply as i32
+            }
+            VariantOutcome::Win(_) => -MATE_SCORE + ply as i32,
+            VariantOutcome::Draw => self.draw_score(position),
+        }
+    }
+
+    fn evaluate(&self, position: &Position) -> i32 {
+        // returns the score of the position for the side to move, including what the style adds
+        let bonus = self.style.bonus(position, self.root_colour);
+        match position.colours_turn == self.root_colour {
+            true => position.evaluate() + bonus,
+            false => position.evaluate() - bonus,
+        }
+    }
+
+    fn order_moves(&self, position: &Position, moves: &mut [Move], ply: usize) {
+        // puts the moves most likely to be best first (so more of the others get cut off)
+        let pv_move = self.previous_pv.get(ply).copied();
+        moves.sort_by_cached_key(|&piece_move| -move_order_score(position, piece_move, pv_move));
+    }
+
+    fn update_pv(&mut self, ply: usize, piece_move: Move) {
+        // makes the move followed by the best line after it the best line from this ply
+        let (this_ply, next_plies) = self.pv_table.split_at_mut(ply + 1);
+        this_ply[ply].clear();
+        this_ply[ply].push(piece_move);
+        this_ply[ply].extend_from_slice(&next_plies[0]);
+    }
+
+    fn negamax(
+        &mut self,
+        position: &mut Position,
+        depth: u32,
+        ply: usize,
+        mut alpha: i32,
+        beta: i32,
+    ) -> i32 {
+        // returns the score of the position for the side to move (alpha and beta are the scores already guaranteed to each side)
+        self.pv_table[ply].clear();
+
+        if self.should_stop() {
+            return 0; // thrown away
+        }
+        if ply > 0 && self.is_draw(position) {
+            return self.draw_score(position);
+        }
+        if let Some(outcome) = position.variant_outcome() {
+            return self.outcome_score(position, outcome, ply);
+        }
+        if depth == 0 || ply >= MAX_PLY - 1 {
+            return self.quiescence(position, ply, alpha, beta);
+        }
+
+        self.nodes += 1;
+
+        let mut moves = position.legal_moves_with_promotions();
+        if moves.is_empty() {
+            return match position.is_in_check() {
+                true => -MATE_SCORE + ply as i32,   // checkmated (sooner is worse)
+                false => self.draw_score(position), // stalemate
+            };
+        }
+        self.order_moves(position, &mut moves, ply);
+
+        for piece_move in moves {
+            let undo_info = position.make_move(piece_move);
+            self.keys.push(position.hash);
+            let score = -self.negamax(position, depth - 1, ply + 1, -beta, -alpha);
+            self.keys.pop();
+            position.unmake_move(undo_info);
+
+            if self.stopped {
+                return 0;
+            }
+
+            if score > alpha {
+                alpha = score;
+                self.update_pv(ply, piece_move);
+
+                if alpha >= beta {
+                    break; // opponent won't allow this line
+                }
+            }
+        }
+
+        alpha
+    }
+
+    fn quiescence(
+        &mut self,
+        position: &mut Position,
+        ply: usize,
+        mut alpha: i32,
+        beta: i32,
+    ) -> i32 {
+        // returns the score once the captures on the board have been played out (so a search doesn't stop halfway through an exchange)
+        self.pv_table[ply].clear();
+        self.nodes += 1;
+
+        if self.should_stop() {
+            return 0;
+        }
+
+        if let Some(outcome) = position.variant_outcome() {
+            return self.outcome_score(position, outcome, ply);
+        }
+
+        let in_check = position.is_in_check();
+        let legal_moves = position.legal_moves_with_promotions();
+        if legal_moves.is_empty() {
+            return match in_check {
+                true => -MATE_SCORE + ply as i32,   // checkmated
+                false => self.draw_score(position), // stalemate
+            };
+        }
+
+        if ply >= MAX_PLY - 1 {
+            return self.evaluate(position);
+        }
+
+        // a side in check has to get out of it, so can't just stand pat and only look at captures
+        if !in_check {
+            let stand_pat = self.evaluate(position); // side to move doesn't have to capture
+            if stand_pat >= beta {
+                return stand_pat;
+            }
+            alpha = alpha.max(stand_pat);
+        }
+
+        let mut moves: Vec<Move> = legal_moves
+            .into_iter()
+            .filter(|&piece_move| {
+                in_check
+                    || ((is_capture(position, piece_move)
+                        || piece_move.flag == MoveFlag::QueenPromo)
+                        && position.static_exchange(piece_move) >= 0) // losing captures won't help
+            })
+            .collect();
+        self.order_moves(position, &mut moves, MAX_PLY); // no best line this deep
+
+        for piece_move in moves {
+            let undo_info = position.make_move(piece_move);
+            let score = -self.quiescence(position, ply + 1, -beta, -alpha);
+            position.unmake_move(undo_info);
+
+            if self.stopped {
+                return 0;
+            }
+
+            if score > alpha {
+                alpha = score;
+                self.update_pv(ply, piece_move);
+
+                if alpha >= beta {
+                    break;
+                }
+            }
+        }
+
+        alpha
+    }
+}
+
+fn iterative_deepening(
+    position: &Position,
+    keys: Vec<u64>,
+    limits: &SearchLimits,
+    style: &SearchStyle,
+    stop: &AtomicBool,
+    mut report: impl FnMut(&SearchResult),
+) -> SearchResult {
+    // searches one ply deeper each time until a limit is reached (each search starts with the last best line)
+    let start_time = Instant::now();
+    let time_budget = limits.time_budget(position.colours_turn);
+    let legal_moves = position.legal_moves_with_promotions();
+
+    let mut result = SearchResult {
+        best_move: legal_moves.first().copied(), // something to play even if there's no time to search
+        ..SearchResult::default()
+    };
+    if legal_moves.is_empty() {
+        return result;
+    }
+
+    let mut searcher = Searcher {
+        stop,
+        root_colour: position.colours_turn,
+        style: *style,
+        deadline: time_budget.map(|budget| start_time + budget),
+        node_limit: limits.nodes,
+        finish_depth: limits.depth.is_some() || limits.nodes.is_some(), // always answer with a searched move
+        nodes: 0,
+        stopped: false,
+        keys,
+        pv_table: vec![Vec::new(); MAX_PLY + 1],
+        previous_pv: Vec::new(),
+    };
+    let mut position = position.clone();
+    let max_depth = limits
+        .depth
+        .unwrap_or(MAX_PLY as u32 - 1)
+        .clamp(1, MAX_PLY as u32 - 1);
+
+    for depth in 1..=max_depth {
+        let score = searcher.negamax(&mut position, depth, 0, -MATE_SCORE - 1, MATE_SCORE + 1);
+        searcher.finish_depth = false;
+
+        if searcher.stopped {
+            break; // unfinished depth, keep the last one
+        }
+
+        searcher.previous_pv = searcher.pv_table[0].clone();
+        result = SearchResult {
+            best_move: searcher.previous_pv.first().copied().or(result.best_move),
+            score,
+            depth,
+            nodes: searcher.nodes,
+            time: start_time.elapsed(),
+            principal_variation: searcher.previous_pv.clone(),
+        };
+        report(&result);
+
+        let mate_plies = (MATE_SCORE - result.score.abs()) as u32; // mate_in counts moves, depth counts plies
+        if result.mate_in().is_some() && mate_plies <= depth {
+            break; // forced mate found within the full depth searched, looking deeper won't change it
+        }
+        if time_budget.is_some_and(|budget| start_time.elapsed() > budget / 2) {
+            break; // next depth would take longer than the time left
+        }
+    }
+
+    result.nodes = searcher.nodes;
+    result.time = start_time.elapsed();
+    result
+}
+
+pub fn search(
+    position: &Position,
+    limits: &SearchLimits,
+    stop: &AtomicBool,
+    report: impl FnMut(&SearchResult),
+) -> SearchResult {
+    // returns the best move in the position within the limits (report is called after every finished depth)
+    let style = SearchStyle::default();
+    iterative_deepening(position, vec![position.hash], limits, &style, stop, report)
+}
+
+pub fn search_game(
+    game: &Game,
+    limits: &SearchLimits,
+    stop: &AtomicBool,
+    report: impl FnMut(&SearchResult),
+) -> SearchResult {
+    // returns the best move in the current position of a game (avoiding repeats of earlier positions)
+    search_game_with_style(game, limits, &SearchStyle::default(), stop, report)
+}
+
+pub fn search_game_with_style(
+    game: &Game,
+    limits: &SearchLimits,
+    style: &SearchStyle,
+    stop: &AtomicBool,
+    report: impl FnMut(&SearchResult),
+) -> SearchResult {
+    // returns the best move in the current position of a game for a side with its own way of playing
+    let mut keys = game.history().keys().to_vec();
+    keys.pop(); // current position (by its full hash below)
+    keys.push(game.position.hash);
+
+    iterative_deepening(&game.position, keys, limits, style, stop, report)
+}
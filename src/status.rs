@@ -0,0 +1,74 @@
+use crate::atomic::kings_touching;
+use crate::draw::DrawReason;
+use crate::movegen;
+use crate::piece::PieceColour;
+use crate::position::Position;
+use crate::variant::{Variant, VariantOutcome};
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum GameStatus {
+    // state of the game for the player to move
+    Ongoing { in_check: bool },
+    Checkmate(PieceColour),  // colour of the winner
+    VariantWin(PieceColour), // colour of the winner by the variant's own rules (e.g: king of the hill)
+    Stalemate,
+    Draw(DrawReason),
+}
+
+impl Position {
+    pub fn is_in_check(&self) -> bool {
+        // returns true if the king of the player to move is attacked
+        let king_index = match self.colours_turn {
+            PieceColour::White => self.king_indexes.0,
+            PieceColour::Black => self.king_indexes.1,
+            PieceColour::None => -1,
+        };
+
+        match self.variant {
+            Variant::Antichess => return false, // there is no check
+            Variant::Atomic if kings_touching(self.king_indexes) => return false, // taking the king would blow up the other one too
+            _ => (),
+        }
+
+        king_index != -1 && movegen::square_attacked(self, king_index, self.colours_turn.opponent())
+    }
+
+    pub fn status(&self) -> GameStatus {
+        // returns whether the game is still going, and if not how it ended (repetitions need a PositionHistory)
+        match self.variant_outcome() {
+            Some(VariantOutcome::Win(winner)) => return GameStatus::VariantWin(winner),
+            Some(VariantOutcome::Draw) => return GameStatus::Draw(DrawReason::VariantRule),
+            None => (),
+        }
+
+        let in_check = self.is_in_check();
+
+        if self.legal_moves().is_empty() {
+            return match in_check {
+                true => GameStatus::Checkmate(self.colours_turn.opponent()), // no moves and in check
+                false => GameStatus::Stalemate, // no moves but not in check
+            };
+        }
+
+        if self.is_insufficient_material() {
+            return GameStatus::Draw(DrawReason::InsufficientMaterial);
+        }
+
+        if self.halfmove_clock >= 150 {
+            // a checkmate on the 75th move still counts (checked above)
+            return GameStatus::Draw(DrawReason::SeventyFiveMoveRule);
+        }
+
+        GameStatus::Ongoing { in_check }
+    }
+
+    pub fn is_checkmate(&self) -> bool {
+        // returns true if the player to move has been checkmated
+        matches!(self.status(), GameStatus::Checkmate(_))
+    }
+
+    pub fn is_stalemate(&self) -> bool {
+        // returns true if the player to move has no legal moves but is not in check
+        self.status() == GameStatus::Stalemate
+    }
+}
@@ -0,0 +1,246 @@
+use std::io::{BufRead, Write};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread::{self, JoinHandle};
+use std::time::Duration;
+
+use crate::fen::FenError;
+use crate::game::Game;
+use crate::position::Position;
+use crate::san::MoveParseError;
+use crate::search::{self, SearchLimits, SearchResult};
+
+const ENGINE_NAME: &str = "Rust-Chess";
+const ENGINE_AUTHOR: &str = "Jam0230";
+
+type SharedOutput = Arc<Mutex<Box<dyn Write + Send>>>;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum UciError {
+    // reasons a command from the gui can't be followed
+    UnknownCommand(String),
+    BadFen(FenError),
+    BadMove {
+        text: String,
+        reason: MoveParseError,
+    },
+}
+
+fn position_command(arguments: &[&str], chess960: bool) -> Result<Game, UciError> {
+    // returns the game given by 'position [startpos | fen <fen>] moves <moves>' (castling is king takes rook in chess960)
+    let moves_index = arguments
+        .iter()
+        .position(|&argument| argument == "moves")
+        .unwrap_or(arguments.len());
+
+    let mut start_position = match arguments.first() {
+        Some(&"startpos") => Position::start(),
+        Some(&"fen") => {
+            Position::from_fen(&arguments[1..moves_index].join(" ")).map_err(UciError::BadFen)?
+        }
+        _ => return Err(UciError::UnknownCommand(arguments.join(" "))),
+    };
+    start_position.chess960 |= chess960;
+
+    let mut game = Game::new(start_position);
+
+    for &move_text in arguments.iter().skip(moves_index + 1) {
+        let piece_move =
+            game.position
+                .parse_move(move_text)
+                .map_err(|reason| UciError::BadMove {
+                    text: move_text.to_string(),
+                    reason,
+                })?;
+        game.make_move(piece_move);
+    }
+
+    Ok(game)
+}
+
+fn setoption_command(arguments: &[&str]) -> Result<(String, String), UciError> {
+    // returns the name and value given by 'setoption name <name> [value <value>]'
+    let value_index = arguments
+        .iter()
+        .position(|&argument| argument == "value")
+        .unwrap_or(arguments.len());
+
+    match arguments.first() {
+        Some(&"name") if value_index > 1 => Ok((
+            arguments[1..value_index].join(" "),
+            arguments.get(value_index + 1..).unwrap_or(&[]).join(" "),
+        )),
+        _ => Err(UciError::UnknownCommand(format!(
+            "setoption {}",
+            arguments.join(" ")
+        ))),
+    }
+}
+
+fn go_command(arguments: &[&str]) -> Result<SearchLimits, UciError> {
+    // returns the search limits given by 'go <limits>'
+    let mut limits = SearchLimits::default();
+    let mut arguments = arguments.iter();
+
+    while let Some(&name) = arguments.next() {
+        if name == "infinite" {
+            limits.infinite = true;
+            continue;
+        }
+        if name == "ponder" {
+            continue; // pondering isn't supported, think as normal
+        }
+
+        let value = match arguments.next().and_then(|value| value.parse::<i64>().ok()) {
+            Some(value) => value.max(0) as u64, // guis can send negative clock times
+            None => return Err(UciError::UnknownCommand(format!("go {}", name))),
+        };
+        let milliseconds = Duration::from_millis(value);
+
+        match name {
+            "depth" => limits.depth = Some(value as u32),
+            "nodes" => limits.nodes = Some(value),
+            "movetime" => limits.move_time = Some(milliseconds),
+            "wtime" => limits.time_left.0 = Some(milliseconds),
+            "btime" => limits.time_left.1 = Some(milliseconds),
+            "winc" => limits.increment.0 = milliseconds,
+            "binc" => limits.increment.1 = milliseconds,
+            "movestogo" => limits.moves_to_go = Some(value),
+            "mate" => limits.depth = Some(value as u32 * 2), // look far enough to see the mate
+            _ => return Err(UciError::UnknownCommand(format!("go {}", name))),
+        }
+    }
+
+    Ok(limits)
+}
+
+fn send(output: &SharedOutput, line: &str) {
+    // writes a line to the gui
+    let mut output = output
+        .lock()
+        .expect("\x1b[41m--UCI OUTPUT POISONED--\x1b[0m");
+    let _ = writeln!(output, "{}", line); // nothing can be done if the gui has gone
+    let _ = output.flush();
+}
+
+fn info_line(result: &SearchResult) -> String {
+    // returns the info line for a finished depth of the search
+    let score = match result.mate_in() {
+        Some(mate_in) => format!("mate {}", mate_in),
+        None => format!("cp {}", result.score),
+    };
+    let milliseconds = result.time.as_millis().max(1);
+    let principal_variation: Vec<String> = result
+        .principal_variation
+        .iter()
+        .map(|piece_move| piece_move.to_long_algebraic())
+        .collect();
+
+    format!(
+        "info depth {} score {} nodes {} nps {} time {} pv {}",
+        result.depth,
+        score,
+        result.nodes,
+        result.nodes as u128 * 1000 / milliseconds,
+        result.time.as_millis(),
+        principal_variation.join(" ")
+    )
+}
+
+fn think(game: Game, limits: SearchLimits, stop: Arc<AtomicBool>, output: SharedOutput) {
+    // looks for the best move and reports it to the gui
+    let result = search::search_game(&game, &limits, &stop, |result| {
+        send(&output, &info_line(result))
+    });
+
+    while limits.infinite && !stop.load(Ordering::Relaxed) {
+        thread::sleep(Duration::from_millis(5)); // 'go infinite' only answers once told to stop
+    }
+
+    match result.best_move {
+        Some(best_move) => send(
+            &output,
+            &format!("bestmove {}", best_move.to_long_algebraic()),
+        ),
+        None => send(&output, "bestmove 0000"), // no legal moves (checkmate or stalemate)
+    }
+}
+
+fn stop_search(stop: &AtomicBool, search_thread: &mut Option<JoinHandle<()>>) {
+    // stops the search running (if any) and waits for it to send its best move
+    stop.store(true, Ordering::Relaxed);
+    if let Some(search_thread) = search_thread.take() {
+        let _ = search_thread.join();
+    }
+}
+
+pub fn run<R: BufRead, W: Write + Send + 'static>(input: R, output: W) {
+    // talks to a gui with the universal chess interface until told to quit (or the input ends)
+    let output: SharedOutput = Arc::new(Mutex::new(Box::new(output)));
+    let mut game = Game::new(Position::start());
+    let stop = Arc::new(AtomicBool::new(false));
+    let mut search_thread: Option<JoinHandle<()>> = None;
+    let mut chess960 = false; // UCI_Chess960 option
+
+    for line in input.lines() {
+        let line = match line {
+            Ok(line) => line,
+            Err(_) => break,
+        };
+        let words: Vec<&str> = line.split_whitespace().collect();
+
+        let command_result = match words.first() {
+            Some(&"uci") => {
+                send(&output, &format!("id name {}", ENGINE_NAME));
+                send(&output, &format!("id author {}", ENGINE_AUTHOR));
+                send(&output, "option name UCI_Chess960 type check default false");
+                send(&output, "uciok");
+                Ok(())
+            }
+            Some(&"isready") => {
+                send(&output, "readyok");
+                Ok(())
+            }
+            Some(&"setoption") => setoption_command(&words[1..]).map(|(name, value)| {
+                if name.eq_ignore_ascii_case("UCI_Chess960") {
+                    chess960 = value.eq_ignore_ascii_case("true");
+                }
+            }),
+            Some(&"ucinewgame") => {
+                stop_search(&stop, &mut search_thread);
+                game = Game::new(Position::start());
+                Ok(())
+            }
+            Some(&"position") => {
+                stop_search(&stop, &mut search_thread);
+                position_command(&words[1..], chess960).map(|new_game| game = new_game)
+            }
+            Some(&"go") => go_command(&words[1..]).map(|limits| {
+                stop_search(&stop, &mut search_thread);
+                stop.store(false, Ordering::Relaxed);
+
+                let (game, stop, output) = (game.clone(), stop.clone(), output.clone());
+                search_thread = Some(thread::spawn(move || think(game, limits, stop, output)));
+            }),
+            Some(&"stop") => {
+                stop_search(&stop, &mut search_thread);
+                Ok(())
+            }
+            Some(&"quit") => break,
+            Some(_) => Err(UciError::UnknownCommand(line.clone())),
+            None => Ok(()), // blank line
+        };
+
+        if let Err(uci_error) = command_result {
+            // guis ignore info strings they don't understand, so this is safe to send
+            let message = match uci_error {
+                UciError::UnknownCommand(command) => format!("unknown command '{}'", command),
+                UciError::BadFen(fen_error) => format!("not a valid fen string: {}", fen_error),
+                UciError::BadMove { text, reason } => format!("can't play '{}': {}", text, reason),
+            };
+            send(&output, &format!("info string {}", message));
+        }
+    }
+
+    stop_search(&stop, &mut search_thread); // finish any search before quitting
+}
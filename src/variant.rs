@@ -0,0 +1,229 @@
+use crate::bitboard::{colour_index, squares};
+use crate::evaluation::piece_value;
+use crate::piece::{PieceColour, PieceType};
+use crate::position::{Move, Position, START_FEN};
+
+pub const CHECKS_TO_WIN: u32 = 3; // checks needed to win three-check
+const HILL: [i32; 4] = [27, 28, 35, 36]; // d5, e5, d4, e4
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default)]
+pub enum Variant {
+    // set of rules a game is played by
+    #[default]
+    Standard,
+    KingOfTheHill, // a king reaching the middle four squares wins
+    ThreeCheck,    // checking the other king three times wins
+    RacingKings,   // no checks allowed, first king to the eighth rank wins
+    Crazyhouse,    // captured pieces join the capturer's side and can be dropped back on the board
+    Atomic, // captures blow up everything but pawns around them, blowing up the other king wins
+    Antichess, // taking is forced, no check, losing every piece (or being stalemated) wins
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum VariantOutcome {
+    // how a variant's own rules ended the game (checkmate and stalemate are looked for separately)
+    Win(PieceColour),
+    Draw,
+}
+
+impl Variant {
+    pub const ALL: [Variant; 7] = [
+        Variant::Standard,
+        Variant::KingOfTheHill,
+        Variant::ThreeCheck,
+        Variant::RacingKings,
+        Variant::Crazyhouse,
+        Variant::Atomic,
+        Variant::Antichess,
+    ];
+
+    pub fn name(self) -> &'static str {
+        // returns the name of the variant (as used in pgn Variant tags)
+        match self {
+            Variant::Standard => "Standard",
+            Variant::KingOfTheHill => "King of the Hill",
+            Variant::ThreeCheck => "Three-check",
+            Variant::RacingKings => "Racing Kings",
+            Variant::Crazyhouse => "Crazyhouse",
+            Variant::Atomic => "Atomic",
+            Variant::Antichess => "Antichess",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Variant> {
+        // returns the variant with the given name (ignoring case, spaces and dashes)
+        let simplify = |name: &str| name.to_lowercase().replace([' ', '-', '_'], "");
+        let name = simplify(name);
+
+        Variant::ALL
+            .into_iter()
+            .find(|variant| simplify(variant.name()) == name)
+            .or(match name.as_str() {
+                "chess" | "normal" => Some(Variant::Standard),
+                "koth" => Some(Variant::KingOfTheHill),
+                "3check" => Some(Variant::ThreeCheck),
+                "losingchess" | "suicide" | "giveaway" => Some(Variant::Antichess),
+                _ => None,
+            })
+    }
+
+    pub fn start_fen(self) -> &'static str {
+        // returns the fen of the position games of the variant start from
+        match self {
+            Variant::ThreeCheck => "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 3+3 0 1",
+            Variant::RacingKings => "8/8/8/8/8/8/krbnNBRK/qrbnNBRQ w - - 0 1",
+            Variant::Antichess => "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w - - 0 1", // no castling
+            Variant::Crazyhouse => "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR[] w KQkq - 0 1",
+            Variant::Standard | Variant::KingOfTheHill | Variant::Atomic => START_FEN,
+        }
+    }
+
+    pub fn start_position(self) -> Position {
+        // returns the position games of the variant start from
+        Position::from_fen_with_variant(self.start_fen(), self)
+            .expect("\x1b[41m--INVALID VARIANT START FEN--\x1b[0m")
+    }
+
+    pub fn win_description(self) -> &'static str {
+        // returns how the variant's own rules are won (for end of game messages)
+        match self {
+            Variant::KingOfTheHill => "KING REACHED THE HILL",
+            Variant::ThreeCheck => "THIRD CHECK",
+            Variant::RacingKings => "KING REACHED THE EIGHTH RANK",
+            Variant::Atomic => "KING BLOWN UP",
+            Variant::Antichess => "NO MOVES LEFT",
+            Variant::Standard | Variant::Crazyhouse => "CHECKMATE",
+        }
+    }
+}
+
+impl Position {
+    pub(crate) fn king_index(&self, colour: PieceColour) -> i32 {
+        // returns the square of a colour's king (-1 if it has none)
+        match colour {
+            PieceColour::White => self.king_indexes.0,
+            _ => self.king_indexes.1,
+        }
+    }
+
+    fn king_on_eighth_rank(&self, colour: PieceColour) -> bool {
+        // returns true if the colour's king has reached the top rank of the board (racing kings)
+        (0..8).contains(&self.king_index(colour))
+    }
+
+    pub fn gives_check(&self, piece_move: Move) -> bool {
+        // returns true if the move attacks the other king
+        let mut after_move = self.clone();
+        after_move.make_move(piece_move);
+        after_move.is_in_check()
+    }
+
+    pub fn variant_outcome(&self) -> Option<VariantOutcome> {
+        // returns the result if the variant's own rules have ended the game (None to carry on)
+        let last_mover = self.colours_turn.opponent();
+
+        match self.variant {
+            Variant::Standard | Variant::Crazyhouse => None,
+            Variant::KingOfTheHill => [last_mover, self.colours_turn]
+                .into_iter()
+                .find(|&colour| HILL.contains(&self.king_index(colour)))
+                .map(VariantOutcome::Win),
+            Variant::ThreeCheck => {
+                let (white_checks, black_checks) = self.checks_given;
+                match (white_checks >= CHECKS_TO_WIN, black_checks >= CHECKS_TO_WIN) {
+                    (true, _) => Some(VariantOutcome::Win(PieceColour::White)),
+                    (_, true) => Some(VariantOutcome::Win(PieceColour::Black)),
+                    _ => None,
+                }
+            }
+            Variant::RacingKings => {
+                let white_home = self.king_on_eighth_rank(PieceColour::White);
+                let black_home = self.king_on_eighth_rank(PieceColour::Black);
+
+                match (white_home, black_home) {
+                    (true, true) => Some(VariantOutcome::Draw),
+                    (false, true) => Some(VariantOutcome::Win(PieceColour::Black)),
+                    (true, false) => {
+                        // black moves second, so gets one move to draw by reaching the eighth rank too
+                        let black_can_follow = self.colours_turn == PieceColour::Black
+                            && self.legal_moves().iter().any(|legal_move| {
+                                self.board[legal_move.start as usize].piece_type == PieceType::King
+                                    && legal_move.end < 8
+                            });
+                        match black_can_follow {
+                            true => None,
+                            false => Some(VariantOutcome::Win(PieceColour::White)),
+                        }
+                    }
+                    (false, false) => None,
+                }
+            }
+            Variant::Atomic => match self.king_indexes {
+                (-1, -1) => None, // no kings to begin with
+                (-1, _) => Some(VariantOutcome::Win(PieceColour::Black)),
+                (_, -1) => Some(VariantOutcome::Win(PieceColour::White)),
+                _ => None,
+            },
+            Variant::Antichess => match self.legal_moves().is_empty() {
+                true => Some(VariantOutcome::Win(self.colours_turn)), // lost every piece or is stalemated
+                false => None,
+            },
+        }
+    }
+
+    pub(crate) fn variant_allows(&self, piece_move: Move) -> bool {
+        // returns false if the variant forbids a move the usual rules allow
+        match self.variant {
+            Variant::RacingKings => !self.gives_check(piece_move),
+            _ => true,
+        }
+    }
+
+    fn material(&self, colour: PieceColour) -> i32 {
+        // returns the worth of the colour's pieces on the board in centipawns (kings count as much as knights)
+        squares(self.bitboards.colours[colour_index(colour)])
+            .map(|index| match self.board[index as usize].piece_type {
+                PieceType::King => piece_value(PieceType::Knight),
+                piece_type => piece_value(piece_type),
+            })
+            .sum()
+    }
+
+    pub(crate) fn variant_evaluation(&self, colour: PieceColour) -> i32 {
+        // returns extra score for how close the colour is to winning by the variant's own rules
+        if self.variant == Variant::Antichess {
+            return -2 * self.material(colour); // turns the usual material count around, fewer pieces is better
+        }
+
+        let king_index = self.king_index(colour);
+        if king_index == -1 {
+            return 0;
+        }
+
+        match self.variant {
+            Variant::KingOfTheHill => {
+                // closer to the hill is better (distance counted in king moves)
+                let distance = HILL
+                    .iter()
+                    .map(|&hill_index| {
+                        ((hill_index % 8) - (king_index % 8))
+                            .abs()
+                            .max(((hill_index / 8) - (king_index / 8)).abs())
+                    })
+                    .min()
+                    .unwrap_or(0);
+                (3 - distance) * 40
+            }
+            Variant::ThreeCheck => {
+                let checks = match colour {
+                    PieceColour::White => self.checks_given.0,
+                    _ => self.checks_given.1,
+                };
+                checks as i32 * 200
+            }
+            Variant::RacingKings => (7 - king_index / 8) * 60, // ranks climbed
+            Variant::Crazyhouse => self.pocket_value(colour),
+            Variant::Standard | Variant::Atomic | Variant::Antichess => 0,
+        }
+    }
+}
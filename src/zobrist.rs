@@ -0,0 +1,164 @@
+use crate::bitboard::{colour_index, squares, type_index, Bitboard};
+use crate::crazyhouse::POCKET_ORDER;
+use crate::piece::{Piece, PieceColour, PieceType};
+use crate::position::Position;
+
+const fn splitmix64(state: u64) -> (u64, u64) {
+    // returns the next random number and generator state (fixed seed so keys are the same every run)
+    let state = state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+    let mut z = state;
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    (z ^ (z >> 31), state)
+}
+
+const fn generate_keys<const N: usize>(seed: u64) -> [u64; N] {
+    // fills a table with random keys
+    let mut keys = [0u64; N];
+    let mut state = seed;
+    let mut i = 0;
+    while i < N {
+        let (key, next_state) = splitmix64(state);
+        keys[i] = key;
+        state = next_state;
+        i += 1;
+    }
+    keys
+}
+
+const PIECE_KEYS: [u64; 12 * 64] = generate_keys(0x5eed_0001); // one key per piece kind per square
+const CASTLING_KEYS: [u64; 4] = generate_keys(0x5eed_0002); // white king side, white queen side, black king side, black queen side
+const EN_PASSANT_KEYS: [u64; 8] = generate_keys(0x5eed_0003); // one key per file
+const BLACK_TO_MOVE_KEY: u64 = splitmix64(0x5eed_0004).0;
+const CHECK_KEYS: [u64; 6] = generate_keys(0x5eed_0005); // first, second and third check given by white then black
+const MAX_POCKET_COUNT: u32 = 16; // most pieces of one type a pocket can hold (every pawn)
+const POCKET_KEYS: [u64; 2 * 5 * MAX_POCKET_COUNT as usize] = generate_keys(0x5eed_0006); // one key per colour, piece type and count
+const PROMOTED_KEYS: [u64; 64] = generate_keys(0x5eed_0007); // one key per square a promoted piece can stand on
+
+pub(crate) fn piece_key(piece: Piece, index: i32) -> u64 {
+    // returns key for a piece standing on a square (0 for empty squares)
+    let type_index = match piece.piece_type {
+        PieceType::Pawn => 0,
+        PieceType::Rook => 1,
+        PieceType::Knight => 2,
+        PieceType::Bishop => 3,
+        PieceType::Queen => 4,
+        PieceType::King => 5,
+        PieceType::None => return 0,
+    };
+    let colour_index = match piece.piece_colour {
+        PieceColour::White => 0,
+        PieceColour::Black => 6,
+        PieceColour::None => return 0,
+    };
+
+    PIECE_KEYS[(colour_index + type_index) * 64 + index as usize]
+}
+
+pub(crate) fn castling_key(castling_rights: (bool, bool, bool, bool)) -> u64 {
+    // returns key for the combination of castling rights
+    let mut key = 0;
+    let rights = [
+        castling_rights.0,
+        castling_rights.1,
+        castling_rights.2,
+        castling_rights.3,
+    ];
+
+    for (right_index, right) in rights.iter().enumerate() {
+        if *right {
+            key ^= CASTLING_KEYS[right_index];
+        }
+    }
+    key
+}
+
+pub(crate) fn en_passant_key(position: &Position) -> u64 {
+    // returns key for the en passant file, only counted when a pawn is beside the pawn that double moved
+    if position.en_passant_move == -1 {
+        return 0;
+    }
+
+    let pushed_pawn_index = match position.colours_turn {
+        PieceColour::White => position.en_passant_move + 8, // black pawn moved past the target
+        PieceColour::Black => position.en_passant_move - 8, // white pawn moved past the target
+        PieceColour::None => return 0,
+    };
+
+    let capturing_pawn = Piece {
+        piece_type: PieceType::Pawn,
+        piece_colour: position.colours_turn,
+    };
+
+    for side in [-1i32, 1] {
+        let file = (pushed_pawn_index % 8) + side;
+        if (0..=7).contains(&file)
+            && position.board[(pushed_pawn_index + side) as usize] == capturing_pawn
+        {
+            return EN_PASSANT_KEYS[(position.en_passant_move % 8) as usize];
+        }
+    }
+
+    0
+}
+
+pub(crate) fn checks_key(checks_given: (u32, u32)) -> u64 {
+    // returns key for the number of checks each side has given (0 when neither has, as in normal chess)
+    let white_key = match checks_given.0.min(3) {
+        0 => 0,
+        checks => CHECK_KEYS[checks as usize - 1],
+    };
+    let black_key = match checks_given.1.min(3) {
+        0 => 0,
+        checks => CHECK_KEYS[checks as usize + 2],
+    };
+
+    white_key ^ black_key
+}
+
+pub(crate) fn pocket_key(colour: PieceColour, piece_type: PieceType, count: u32) -> u64 {
+    // returns key for a colour holding a number of pieces of a type (0 when it holds none, as in normal chess)
+    if count == 0 || colour == PieceColour::None || type_index(piece_type) > 4 {
+        return 0;
+    }
+
+    let count_index = (count.min(MAX_POCKET_COUNT) - 1) as usize;
+    POCKET_KEYS[(colour_index(colour) * 5 + type_index(piece_type)) * MAX_POCKET_COUNT as usize
+        + count_index]
+}
+
+pub(crate) fn promoted_key(promoted: Bitboard) -> u64 {
+    // returns key for the squares of promoted pieces (0 when there are none, as in normal chess)
+    squares(promoted).fold(0, |key, index| key ^ PROMOTED_KEYS[index as usize])
+}
+
+pub(crate) fn side_key(colours_turn: PieceColour) -> u64 {
+    // returns key for the side to move
+    match colours_turn {
+        PieceColour::Black => BLACK_TO_MOVE_KEY,
+        _ => 0,
+    }
+}
+
+impl Position {
+    pub fn compute_hash(&self) -> u64 {
+        // returns zobrist key of the position worked out from scratch (make_move keeps self.hash up to date incrementally)
+        let mut hash = 0;
+
+        for (index, piece) in self.board.iter().enumerate() {
+            hash ^= piece_key(*piece, index as i32);
+        }
+
+        for colour in [PieceColour::White, PieceColour::Black] {
+            for piece_type in POCKET_ORDER {
+                hash ^= pocket_key(colour, piece_type, self.pocket_count(colour, piece_type));
+            }
+        }
+
+        hash ^ side_key(self.colours_turn)
+            ^ castling_key(self.castling_rights)
+            ^ en_passant_key(self)
+            ^ checks_key(self.checks_given)
+            ^ promoted_key(self.promoted)
+    }
+}
@@ -0,0 +1,84 @@
+use chess::perft::perft;
+use chess::{GameStatus, MoveFlag, PieceColour, Position, Variant};
+
+fn antichess(fen: &str) -> Position {
+    Position::from_fen_with_variant(fen, Variant::Antichess).unwrap()
+}
+
+#[test]
+fn antichess_perft() {
+    let start = Variant::Antichess.start_position();
+    assert_eq!(perft(&start, 3), 8067);
+    assert_eq!(perft(&start, 4), 153299);
+
+    let pawns = antichess("8/2p5/8/8/8/8/P7/8 w - - 0 1");
+    assert_eq!(perft(&pawns, 9), 12); // the first promotions (kings included)
+    assert_eq!(perft(&pawns, 11), 312);
+
+    let game_over = antichess("8/1p6/8/8/8/8/P7/8 w - - 0 1");
+    assert_eq!(perft(&game_over, 5), 1);
+    assert_eq!(perft(&game_over, 6), 0);
+}
+
+#[test]
+fn captures_are_forced() {
+    let position = antichess("4k3/8/8/3p4/4P3/8/8/R3K3 w - - 0 1");
+    assert_eq!(position.legal_moves().len(), 1); // exd5 is the only move
+    assert!(position.legal_moves_from(56).is_empty()); // the rook has to wait
+    assert!(position.parse_move("Ra8").is_err());
+
+    // no check, and the king is just another piece to take
+    let mut position = antichess("4k3/8/8/8/8/8/8/4R2K w - - 0 1");
+    assert_eq!(position.status(), GameStatus::Ongoing { in_check: false });
+    let capture = position.parse_move("Rxe8").unwrap();
+    let before = position.clone();
+    let undo_info = position.make_move(capture);
+    assert_eq!(position.king_indexes.1, -1);
+    position.unmake_move(undo_info);
+    assert_eq!(position, before);
+}
+
+#[test]
+fn kings_can_be_promoted_to() {
+    let mut position = antichess("8/P7/8/8/8/8/8/7k w - - 0 1");
+    assert_eq!(position.legal_moves_with_promotions().len(), 5);
+
+    let promotion = position.parse_move("a8=K").unwrap();
+    assert_eq!(promotion.flag, MoveFlag::KingPromo);
+    assert_eq!(promotion.to_long_algebraic(), "a7a8k");
+    assert_eq!(position.move_to_san(promotion), "a8=K");
+
+    position.make_move(promotion);
+    assert_eq!(position.to_fen(), "K7/8/8/8/8/8/8/7k b - - 0 1");
+    assert_eq!(position.king_indexes.0, 0);
+
+    // not in normal chess
+    let position = Position::from_fen("8/P7/8/8/8/8/8/k6K w - - 0 1").unwrap();
+    assert!(position.parse_move("a8=K").is_err());
+}
+
+#[test]
+fn losing_everything_wins() {
+    // white has no pieces left
+    let position = antichess("8/8/8/8/8/8/8/7k w - - 0 1");
+    assert_eq!(
+        position.status(),
+        GameStatus::VariantWin(PieceColour::White)
+    );
+
+    // black is stalemated
+    let position = antichess("8/8/8/8/8/p7/P7/8 b - - 0 1");
+    assert_eq!(
+        position.status(),
+        GameStatus::VariantWin(PieceColour::Black)
+    );
+
+    // taking is forced even when it gives the game away
+    let mut position = antichess("8/8/8/8/8/8/1p6/2R5 b - - 0 1");
+    assert!(position.parse_move("b1=Q").is_err());
+    position.make_move(position.parse_move("bxc1=Q").unwrap());
+    assert_eq!(
+        position.status(),
+        GameStatus::VariantWin(PieceColour::White)
+    );
+}
@@ -0,0 +1,78 @@
+use chess::perft::perft;
+use chess::{GameStatus, PieceColour, Position, Variant};
+
+fn atomic(fen: &str) -> Position {
+    Position::from_fen_with_variant(fen, Variant::Atomic).unwrap()
+}
+
+#[test]
+fn captures_explode() {
+    let mut position = atomic("4k3/8/2p1r3/3n4/4B3/8/8/3QK3 w - - 0 1");
+    let before = position.clone();
+
+    let capture = position.parse_move("Qxd5").unwrap();
+    let undo_info = position.make_move(capture);
+    assert_eq!(position.to_fen(), "4k3/8/2p5/8/8/8/8/4K3 b - - 0 1"); // the pawn survives
+    assert_eq!(position.hash, position.compute_hash());
+
+    position.unmake_move(undo_info);
+    assert_eq!(position, before);
+}
+
+#[test]
+fn king_rules() {
+    // kings can't take, and nothing can blow up its own king
+    let position = atomic("4k3/8/8/8/8/8/3p4/3QK3 w - - 0 1");
+    assert!(position.parse_move("Kxd2").is_err());
+    assert!(position.parse_move("Qxd2").is_err());
+    assert!(position.parse_move("Kf2").is_ok());
+
+    // kings next to each other can't be checked
+    let position = atomic("4q3/8/8/8/8/8/3kK3/8 w - - 0 1");
+    assert_eq!(position.status(), GameStatus::Ongoing { in_check: false });
+    assert!(position.parse_move("Ke3").is_ok());
+
+    // blowing up the other king wins
+    let mut position = atomic("4k3/4p3/8/8/8/8/8/4R1K1 w - - 0 1");
+    let capture = position.parse_move("Rxe7").unwrap();
+    position.make_move(capture);
+    assert_eq!(position.king_indexes.1, -1);
+    assert_eq!(
+        position.status(),
+        GameStatus::VariantWin(PieceColour::White)
+    );
+    assert!(position.legal_moves().is_empty());
+}
+
+#[test]
+fn atomic_perft() {
+    for (fen, counts) in [
+        (
+            "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1",
+            &[20, 400, 8902, 197326][..],
+        ),
+        (
+            "rn2kb1r/1pp1p2p/p2q1pp1/3P4/2P3b1/4PN2/PP3PPP/R2QKB1R b KQkq - 0 1",
+            &[40, 1238, 45237],
+        ),
+        (
+            "rn1qkb1r/p5pp/2p5/3p4/N3P3/5P2/PPP4P/R1BQK3 w Qkq - 0 1",
+            &[28, 833, 23353],
+        ),
+        // chess960 castling next to the other king
+        ("8/8/8/8/8/8/2k5/rR4KR w KQ - 0 1", &[18, 180, 4364]),
+        ("r3k1rR/5K2/8/8/8/8/8/8 b kq - 0 1", &[25, 282, 6753]),
+        ("Rr2k1rR/3K4/3p4/8/8/8/7P/8 w kq - 0 1", &[21, 465, 10631]),
+    ] {
+        let position = atomic(fen);
+        for (depth, &count) in counts.iter().enumerate() {
+            assert_eq!(
+                perft(&position, depth as u32 + 1),
+                count,
+                "{} at depth {}",
+                fen,
+                depth + 1
+            );
+        }
+    }
+}
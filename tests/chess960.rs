@@ -0,0 +1,105 @@
+use chess::chess960::{back_rank, STANDARD_NUMBER};
+use chess::perft::perft;
+use chess::{Game, PgnTags, Position, START_FEN};
+
+fn assert_perft(fen: &str, expected_counts: &[u64]) {
+    let position = Position::from_fen(fen).unwrap();
+    assert!(position.chess960);
+
+    for (depth, expected) in expected_counts.iter().enumerate() {
+        assert_eq!(
+            perft(&position, depth as u32 + 1),
+            *expected,
+            "perft({}) of {}",
+            depth + 1,
+            fen
+        );
+    }
+}
+
+#[test]
+fn numbered_start_positions() {
+    assert_eq!(back_rank(STANDARD_NUMBER).unwrap(), "RNBQKBNR");
+    assert_eq!(back_rank(0).unwrap(), "BBQNNRKR");
+    assert_eq!(back_rank(959).unwrap(), "RKRNNQBB");
+    assert_eq!(back_rank(960), None);
+
+    let standard = Position::chess960(STANDARD_NUMBER).unwrap();
+    assert_eq!(standard.to_fen(), START_FEN);
+    assert_eq!(standard.legal_moves().len(), 20);
+}
+
+#[test]
+fn shredder_and_x_fen_castling() {
+    let shredder = "bqnb1rkr/pp3ppp/3ppn2/2p5/5P2/P2P4/NPP1P1PP/BQ1BNRKR w HFhf - 2 9";
+    let position = Position::from_fen(shredder).unwrap();
+    assert_eq!(position.castling_rooks, (63, 61, 7, 5));
+    assert_eq!(
+        position.to_fen(),
+        "bqnb1rkr/pp3ppp/3ppn2/2p5/5P2/P2P4/NPP1P1PP/BQ1BNRKR w KQkq - 2 9"
+    );
+
+    // an inner rook is named by its file when another rook is further out
+    let inner_rook = Position::from_fen("4k3/8/8/8/8/8/8/1R2K1RR w G - 0 1").unwrap();
+    assert_eq!(inner_rook.castling_rooks.0, 62);
+    assert_eq!(inner_rook.to_fen(), "4k3/8/8/8/8/8/8/1R2K1RR w G - 0 1");
+
+    assert!(Position::from_fen("4k3/8/8/8/8/8/8/R3K2R w E - 0 1").is_err()); // king's own file
+}
+
+#[test]
+fn castling_where_the_king_and_rook_start_anywhere() {
+    // king on b1 castles queen side to c1 with the rook on a1 going to d1
+    let mut position = Position::from_fen("4k3/8/8/8/8/8/8/RK6 w A - 0 1").unwrap();
+    let castle = position.parse_move("O-O-O").unwrap();
+    assert_eq!(castle.to_long_algebraic(), "b1a1");
+
+    let undo_info = position.make_move(castle);
+    assert_eq!(position.to_fen(), "4k3/8/8/8/8/8/8/2KR4 b - - 1 1");
+    assert_eq!(position.king_indexes.0, 58);
+    position.unmake_move(undo_info);
+    assert_eq!(position.to_fen(), "4k3/8/8/8/8/8/8/RK6 w Q - 0 1");
+
+    // king on g1 stays put while the rook jumps to f1
+    let mut position = Position::from_fen("4k3/8/8/8/8/8/8/6KR w H - 0 1").unwrap();
+    position.make_move(position.parse_move("O-O").unwrap());
+    assert_eq!(position.to_fen(), "4k3/8/8/8/8/8/8/5RK1 b - - 1 1");
+
+    // the castling rook can't shield the king's path
+    let position = Position::from_fen("4k3/8/8/8/8/8/8/rR1K4 w B - 0 1").unwrap();
+    assert!(position.parse_move("O-O-O").is_err());
+}
+
+#[test]
+fn chess960_perft() {
+    assert_perft(
+        "bqnb1rkr/pp3ppp/3ppn2/2p5/5P2/P2P4/NPP1P1PP/BQ1BNRKR w HFhf - 2 9",
+        &[21, 528, 12189, 326672],
+    );
+    assert_perft(
+        "2nnrbkr/p1qppppp/8/1ppb4/6PP/3PP3/PPP2P2/BQNNRBKR w HEhe - 1 9",
+        &[21, 807, 18002, 667366],
+    );
+    assert_perft(
+        "b1q1rrkb/pppppppp/3nn3/8/P7/1PPP4/4PPPP/BQNNRKRB w GE - 1 9",
+        &[20, 479, 10471, 273318],
+    );
+}
+
+#[test]
+fn pgn_keeps_the_variant() {
+    // 518 looks like the usual start but castles as king takes rook
+    let mut game = Game::new(Position::chess960(STANDARD_NUMBER).unwrap());
+    for move_text in ["e4", "e5", "Nf3", "Nc6", "Bc4", "Bc5", "O-O"] {
+        let piece_move = game.position.parse_move(move_text).unwrap();
+        game.make_move(piece_move);
+    }
+    assert_eq!(game.moves().last().unwrap().to_long_algebraic(), "e1h1");
+
+    let pgn = game.to_pgn(&PgnTags::new());
+    assert!(pgn.contains("[Variant \"Chess960\"]"));
+
+    let (loaded, _) = Game::from_pgn(&pgn).unwrap();
+    assert!(loaded.start_position.chess960);
+    assert_eq!(loaded.moves(), game.moves());
+}
@@ -0,0 +1,94 @@
+use std::sync::atomic::AtomicBool;
+
+use chess::computer::MAX_LEVEL;
+use chess::search::search_game_with_style;
+use chess::{
+    ComputerPlayer, Game, PieceColour, Player, PlayerAction, Position, SearchLimits, SearchStyle,
+};
+
+fn depth_limit(depth: u32) -> SearchLimits {
+    SearchLimits {
+        depth: Some(depth),
+        ..SearchLimits::default()
+    }
+}
+
+#[test]
+fn full_strength_finds_mate() {
+    let mut game = Game::new(Position::from_fen("6k1/5ppp/8/8/8/8/8/R5K1 w - - 0 1").unwrap());
+    let mut computer = ComputerPlayer::new(MAX_LEVEL, depth_limit(3), SearchStyle::default());
+    let legal_moves = game.legal_moves();
+
+    assert_eq!(computer.random_move_chance(), 0);
+    assert_eq!(
+        computer.choose_action(&game, &legal_moves),
+        PlayerAction::Move(game.position.parse_move("Ra8#").unwrap())
+    );
+}
+
+#[test]
+fn beginner_plays_legal_moves_shallowly() {
+    let mut game = Game::new(Position::start());
+    let mut computer =
+        ComputerPlayer::with_seed(1, SearchLimits::default(), SearchStyle::default(), 7);
+    assert_eq!(computer.random_move_chance(), 90);
+    assert_eq!(computer.search_limits().depth, Some(1));
+
+    for _ in 0..20 {
+        let legal_moves = game.legal_moves();
+        match computer.choose_action(&game, &legal_moves) {
+            PlayerAction::Move(piece_move) => {
+                assert!(legal_moves.contains(&piece_move));
+                game.make_move(piece_move);
+            }
+            action => panic!("expected a move, got {:?}", action),
+        }
+    }
+}
+
+#[test]
+fn contempt_changes_how_draws_are_valued() {
+    // taking the last pawn leaves a lone bishop (a draw), keeping it keeps winning chances
+    let game = Game::new(Position::from_fen("7k/8/7p/8/5B2/8/8/6K1 w - - 0 1").unwrap());
+    let capture = game.position.parse_move("Bxh6").unwrap();
+    let stop = AtomicBool::new(false);
+
+    let neutral = search_game_with_style(
+        &game,
+        &depth_limit(3),
+        &SearchStyle::default(),
+        &stop,
+        |_| {},
+    );
+    assert_ne!(neutral.best_move, Some(capture));
+
+    let drawish = SearchStyle {
+        contempt: -500,
+        ..SearchStyle::default()
+    };
+    let wants_draw = search_game_with_style(&game, &depth_limit(3), &drawish, &stop, |_| {});
+    assert_eq!(wants_draw.best_move, Some(capture));
+
+    let start = Game::new(Position::start());
+    let mut proud = ComputerPlayer::new(
+        5,
+        depth_limit(1),
+        SearchStyle {
+            contempt: 50,
+            aggressiveness: 0,
+        },
+    );
+    let mut peaceful = ComputerPlayer::new(5, depth_limit(1), drawish);
+    assert!(!proud.accepts_draw(&start));
+    assert!(peaceful.accepts_draw(&start));
+}
+
+#[test]
+fn king_pressure_counts_attacks_around_the_king() {
+    let start = Position::start();
+    assert_eq!(start.king_pressure(PieceColour::White), 0);
+
+    let attacked = Position::from_fen("6k1/5ppp/8/6N1/8/8/8/3QK3 w - - 0 1").unwrap();
+    assert!(attacked.king_pressure(PieceColour::White) > 0);
+    assert_eq!(attacked.king_pressure(PieceColour::Black), 0);
+}
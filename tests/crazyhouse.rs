@@ -0,0 +1,147 @@
+use std::sync::atomic::AtomicBool;
+
+use chess::perft::perft;
+use chess::search::search;
+use chess::{
+    FenError, Game, GameStatus, MoveFlag, PgnTags, PieceColour, PieceType, Position, SearchLimits,
+    Variant,
+};
+
+fn play(position: &mut Position, moves: &[&str]) {
+    for text in moves {
+        let piece_move = position.parse_move(text).unwrap();
+        position.make_move(piece_move);
+        assert_eq!(position.hash, position.compute_hash());
+    }
+}
+
+#[test]
+fn captures_fill_the_pocket() {
+    let mut position = Variant::Crazyhouse.start_position();
+    assert_eq!(perft(&position, 4), 197281); // no drops are possible yet
+
+    play(&mut position, &["e4", "d5", "exd5", "Qxd5"]);
+    assert_eq!(
+        position.pocket_count(PieceColour::White, PieceType::Pawn),
+        1
+    );
+    assert_eq!(
+        position.pocket_count(PieceColour::Black, PieceType::Pawn),
+        1
+    );
+    assert_eq!(
+        position.to_fen(),
+        "rnb1kbnr/ppp1pppp/8/3q4/8/8/PPPP1PPP/RNBQKBNR[Pp] w KQkq - 0 3"
+    );
+
+    let drop = position.parse_move("P@e4").unwrap();
+    assert_eq!(drop.flag, MoveFlag::Drop(PieceType::Pawn));
+    assert_eq!(position.parse_move("@e4"), Ok(drop));
+    assert_eq!(position.move_to_san(drop), "P@e4");
+    assert_eq!(drop.to_long_algebraic(), "P@e4");
+
+    let before = position.clone();
+    let undo_info = position.make_move(drop);
+    assert_eq!(
+        position.pocket_count(PieceColour::White, PieceType::Pawn),
+        0
+    );
+    assert_eq!(position.hash, position.compute_hash());
+    position.unmake_move(undo_info);
+    assert_eq!(position, before);
+}
+
+#[test]
+fn drop_rules() {
+    let position = Position::from_fen("2k5/8/8/8/8/8/8/4K3[QRBNPqrbnp] w - - 0 1").unwrap();
+    assert_eq!(position.variant, Variant::Crazyhouse);
+    assert_eq!(perft(&position, 1), 301);
+    assert_eq!(perft(&position, 2), 75353);
+
+    assert!(position.parse_move("P@e8").is_err()); // pawns can't go on the back ranks
+    assert!(position.parse_move("N@c8").is_err()); // square is taken
+
+    // a drop can block a check, so this isn't mate
+    let position = Position::from_fen("k7/8/8/8/8/8/8/r3K3[n] w - - 0 1").unwrap();
+    assert_eq!(position.status(), GameStatus::Ongoing { in_check: true });
+    assert!(Position::from_fen("k7/8/8/8/8/8/8/r3K3[N] w - - 0 1")
+        .unwrap()
+        .parse_move("N@c1")
+        .is_ok());
+
+    // the search knows about drops too
+    let position = Position::from_fen("k7/8/1K6/8/8/8/8/8[Q] w - - 0 1").unwrap();
+    let limits = SearchLimits {
+        depth: Some(2),
+        ..SearchLimits::default()
+    };
+    let result = search(&position, &limits, &AtomicBool::new(false), |_| ());
+    assert_eq!(result.mate_in(), Some(1));
+    assert_eq!(
+        result.best_move.map(|best_move| best_move.flag),
+        Some(MoveFlag::Drop(PieceType::Queen))
+    );
+
+    assert_eq!(
+        Position::from_fen("4k3/8/8/8/8/8/8/4K3[Qk] w - - 0 1"),
+        Err(FenError::BadPocket { position: 21 })
+    );
+    assert!(Position::from_fen("4k3/8/8/8/8/8/8/4K3[Q w - - 0 1").is_err());
+}
+
+#[test]
+fn promoted_pieces_go_back_to_pawns() {
+    let mut position = Position::from_fen("4k3/1P6/8/8/8/8/r7/4K3[] w - - 0 1").unwrap();
+    play(&mut position, &["b8=Q+", "Ke7", "Qb4+", "Ke8", "Qb2"]);
+    assert_eq!(position.to_fen(), "4k3/8/8/8/8/8/rQ~6/4K3[] b - - 4 3");
+
+    let same = Position::from_fen(&position.to_fen()).unwrap();
+    assert_eq!(same, position);
+
+    play(&mut position, &["Rxb2"]);
+    assert_eq!(
+        position.pocket_count(PieceColour::Black, PieceType::Pawn),
+        1
+    );
+    assert_eq!(
+        position.pocket_count(PieceColour::Black, PieceType::Queen),
+        0
+    );
+    assert_eq!(position.to_fen(), "4k3/8/8/8/8/8/1r6/4K3[p] w - - 0 4");
+}
+
+#[test]
+fn promoted_pieces_change_the_hash() {
+    let queen = Position::from_fen("4k3/8/8/8/8/8/rQ6/4K3[] b - - 0 1").unwrap();
+    let promoted_queen = Position::from_fen("4k3/8/8/8/8/8/rQ~6/4K3[] b - - 0 1").unwrap();
+    assert_ne!(queen.hash, promoted_queen.hash);
+
+    // taking them leaves different pockets behind
+    let (mut queen, mut promoted_queen) = (queen, promoted_queen);
+    play(&mut queen, &["Rxb2"]);
+    play(&mut promoted_queen, &["Rxb2"]);
+    assert_ne!(queen.hash, promoted_queen.hash);
+    assert_ne!(queen.to_fen(), promoted_queen.to_fen());
+
+    // a promotion made on the board matches the same position read from a fen
+    let mut position = Position::from_fen("4k3/1P6/8/8/8/8/r7/4K3[] w - - 0 1").unwrap();
+    play(&mut position, &["b8=Q+"]);
+    let from_fen = Position::from_fen("1Q~2k3/8/8/8/8/8/r7/4K3[] b - - 0 1").unwrap();
+    assert_eq!(position.hash, from_fen.hash);
+}
+
+#[test]
+fn pgn_keeps_drops() {
+    let mut game = Game::new(Variant::Crazyhouse.start_position());
+    for move_text in ["e4", "d5", "exd5", "Qxd5", "Nc3", "Qa5", "P@d4"] {
+        let piece_move = game.position.parse_move(move_text).unwrap();
+        game.make_move(piece_move);
+    }
+
+    let pgn = game.to_pgn(&PgnTags::new());
+    assert!(pgn.contains("[Variant \"Crazyhouse\"]"));
+    assert!(pgn.contains("P@d4"));
+
+    let (loaded, _) = Game::from_pgn(&pgn).unwrap();
+    assert_eq!(loaded.position, game.position);
+}
@@ -0,0 +1,116 @@
+use chess::{DrawReason, Game, GameStatus, Position};
+
+fn game_from(fen: &str) -> Game {
+    Game::new(Position::from_fen(fen).unwrap())
+}
+
+fn play(game: &mut Game, moves: &[&str]) {
+    for text in moves {
+        let piece_move = game.position.parse_move(text).unwrap();
+        game.make_move(piece_move);
+    }
+}
+
+#[test]
+fn threefold_claim_and_fivefold_draw() {
+    let mut game = Game::new(Position::start());
+    let knights_out_and_back = ["Nf3", "Nf6", "Ng1", "Ng8"];
+
+    play(&mut game, &knights_out_and_back);
+    assert_eq!(game.history().repetitions(&game.position), 2);
+    assert_eq!(game.claimable_draw(), None);
+
+    play(&mut game, &knights_out_and_back);
+    assert_eq!(game.claimable_draw(), Some(DrawReason::ThreefoldRepetition));
+    assert_eq!(game.status(), GameStatus::Ongoing { in_check: false }); // only if claimed
+
+    play(&mut game, &knights_out_and_back);
+    assert_eq!(game.status(), GameStatus::Ongoing { in_check: false });
+
+    play(&mut game, &knights_out_and_back);
+    assert_eq!(
+        game.status(),
+        GameStatus::Draw(DrawReason::FivefoldRepetition)
+    );
+
+    assert!(game.undo()); // position before Ng8 has only been seen four times
+    assert_eq!(game.status(), GameStatus::Ongoing { in_check: false });
+    assert_eq!(game.claim_draw(), Some(DrawReason::ThreefoldRepetition));
+    assert_eq!(game.claimed_draw(), Some(DrawReason::ThreefoldRepetition));
+}
+
+#[test]
+fn fifty_move_claim_and_seventy_five_move_draw() {
+    let mut game = game_from("8/8/8/4k3/8/8/8/R3K3 w - - 99 80");
+    assert_eq!(game.claimable_draw(), None);
+
+    play(&mut game, &["Ra2"]);
+    assert_eq!(game.position.halfmove_clock, 100);
+    assert_eq!(game.claimable_draw(), Some(DrawReason::FiftyMoveRule));
+    assert_eq!(game.status(), GameStatus::Ongoing { in_check: false });
+    assert_eq!(game.claim_draw(), Some(DrawReason::FiftyMoveRule));
+
+    let mut game = game_from("8/8/8/4k3/8/8/8/R3K3 w - - 149 105");
+    assert_eq!(game.status(), GameStatus::Ongoing { in_check: false });
+
+    play(&mut game, &["Ra2"]);
+    assert_eq!(
+        game.status(),
+        GameStatus::Draw(DrawReason::SeventyFiveMoveRule)
+    );
+}
+
+#[test]
+fn capturable_en_passant_square_changes_the_position() {
+    let capturable = Position::from_fen("4k3/8/8/8/3pP3/8/8/4K3 b - e3 0 1").unwrap();
+    let no_square = Position::from_fen("4k3/8/8/8/3pP3/8/8/4K3 b - - 0 1").unwrap();
+    assert!(capturable.en_passant_capturable());
+    assert_ne!(capturable.repetition_hash(), no_square.repetition_hash());
+
+    let uncapturable = Position::from_fen("4k3/8/8/8/4P3/8/8/4K3 b - e3 0 1").unwrap();
+    let no_square = Position::from_fen("4k3/8/8/8/4P3/8/8/4K3 b - - 0 1").unwrap();
+    assert!(!uncapturable.en_passant_capturable());
+    assert_eq!(uncapturable.repetition_hash(), no_square.repetition_hash());
+
+    // kings step away and back, the first position could take en passant so isn't repeated
+    let king_shuffle = ["Ke7", "Ke2", "Ke8", "Ke1"];
+    let mut game = game_from("4k3/8/8/8/3p4/8/4P3/4K3 w - - 0 1");
+    play(&mut game, &["e4"]);
+    play(&mut game, &king_shuffle);
+    assert_eq!(game.history().repetitions(&game.position), 1);
+    play(&mut game, &king_shuffle);
+    play(&mut game, &king_shuffle);
+    assert_eq!(game.history().repetitions(&game.position), 3);
+
+    let mut game = game_from("4k3/8/8/8/8/8/4P3/4K3 w - - 0 1");
+    play(&mut game, &["e4"]);
+    play(&mut game, &king_shuffle);
+    assert_eq!(game.history().repetitions(&game.position), 2);
+}
+
+#[test]
+fn insufficient_material() {
+    for fen in [
+        "4k3/8/8/8/8/8/8/4K3 w - - 0 1",    // king against king
+        "4k3/8/8/8/8/8/8/1N2K3 w - - 0 1",  // lone knight
+        "4k3/8/8/8/8/8/8/2B1K3 w - - 0 1",  // lone bishop
+        "4kb2/8/8/8/8/8/8/2B1K3 w - - 0 1", // bishops on dark squares
+    ] {
+        let position = Position::from_fen(fen).unwrap();
+        assert!(position.is_insufficient_material(), "{}", fen);
+        assert_eq!(
+            position.status(),
+            GameStatus::Draw(DrawReason::InsufficientMaterial)
+        );
+    }
+
+    for fen in [
+        "2b1k3/8/8/8/8/8/8/2B1K3 w - - 0 1", // bishops on opposite colours can mate
+        "4k3/8/8/8/8/8/8/1NB1K3 w - - 0 1",  // knight and bishop
+        "4k3/8/8/8/8/8/4P3/4K3 w - - 0 1",   // a pawn can promote
+    ] {
+        let position = Position::from_fen(fen).unwrap();
+        assert!(!position.is_insufficient_material(), "{}", fen);
+        assert_eq!(position.status(), GameStatus::Ongoing { in_check: false });
+    }
+}
@@ -0,0 +1,48 @@
+use chess::evaluation::{evaluate, game_phase};
+use chess::{Piece, PieceColour, Position};
+
+fn mirrored(board: &[Piece]) -> Vec<Piece> {
+    // same board with the ranks flipped and the colours swapped
+    (0..64)
+        .map(|index| {
+            let piece = board[index ^ 56];
+            Piece {
+                piece_colour: piece.piece_colour.opponent(),
+                ..piece
+            }
+        })
+        .collect()
+}
+
+#[test]
+fn symmetric_positions_score_the_same_for_either_side() {
+    let start = Position::start();
+    assert_eq!(start.evaluate(), 0);
+    assert_eq!(game_phase(&start.board), 24);
+
+    let kiwipete =
+        Position::from_fen("r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1")
+            .unwrap();
+    let score = evaluate(&kiwipete.board, PieceColour::White);
+
+    assert_eq!(evaluate(&kiwipete.board, PieceColour::Black), -score);
+    assert_eq!(
+        evaluate(&mirrored(&kiwipete.board), PieceColour::Black),
+        score
+    );
+}
+
+#[test]
+fn material_and_squares_count() {
+    let queen_up = Position::from_fen("4k3/8/8/8/8/8/8/3QK3 w - - 0 1").unwrap();
+    assert!(queen_up.evaluate() > 800);
+
+    let queen_up_black_to_move = Position::from_fen("4k3/8/8/8/8/8/8/3QK3 b - - 0 1").unwrap();
+    assert_eq!(queen_up_black_to_move.evaluate(), -queen_up.evaluate());
+
+    // with only kings and pawns left a central king is better than one in the corner
+    let central_king = Position::from_fen("7k/8/8/8/3K4/8/P7/8 w - - 0 1").unwrap();
+    let corner_king = Position::from_fen("7k/8/8/8/8/8/P7/K7 w - - 0 1").unwrap();
+    assert_eq!(game_phase(&central_king.board), 0);
+    assert!(central_king.evaluate() > corner_king.evaluate());
+}
@@ -0,0 +1,45 @@
+use chess::Position;
+
+fn exchange(fen: &str, move_text: &str) -> i32 {
+    // static exchange score of a move in a position
+    let position = Position::from_fen(fen).unwrap();
+    let piece_move = position.parse_move(move_text).unwrap();
+    position.static_exchange(piece_move)
+}
+
+#[test]
+fn undefended_and_defended_pieces() {
+    assert_eq!(exchange("4k3/8/8/3n4/8/8/8/3RK3 w - - 0 1", "Rxd5"), 300);
+    assert_eq!(exchange("4k3/4p3/3p4/8/8/8/8/3QK3 w - - 0 1", "Qxd6"), -800);
+    assert_eq!(exchange("4k3/8/8/4pP2/8/8/8/4K3 w - e6 0 1", "fxe6"), 100);
+    assert_eq!(exchange("4k3/8/8/8/8/8/8/3QK3 w - - 0 1", "Qd3"), 0);
+    assert_eq!(exchange("4k3/8/8/8/8/8/8/3QK3 w - - 0 1", "Qd7+"), -900); // quiet moves can hang pieces too
+
+    let position = Position::from_fen("4k3/4p3/3p4/8/8/8/8/3QK3 w - - 0 1").unwrap();
+    assert!(position.is_losing_capture(position.parse_move("Qxd6").unwrap()));
+    assert!(!position.is_losing_capture(position.parse_move("Qd2").unwrap()));
+}
+
+#[test]
+fn pieces_behind_join_in() {
+    // rooks doubled on the d file win the pawn, one rook alone loses itself for it
+    assert_eq!(exchange("3rk3/8/8/3p4/8/8/3R4/3RK3 w - - 0 1", "Rxd5"), 100);
+    assert_eq!(exchange("3rk3/8/8/3p4/8/8/3R4/4K3 w - - 0 1", "Rxd5"), -400);
+}
+
+#[test]
+fn king_only_takes_undefended_pieces() {
+    assert_eq!(exchange("4k3/4p3/8/8/8/8/4R3/4RK2 w - - 0 1", "Rxe7+"), 100);
+    assert_eq!(exchange("4k3/4p3/8/8/8/8/4R3/5K2 w - - 0 1", "Rxe7+"), -400);
+}
+
+#[test]
+fn mvv_lva_puts_big_victims_and_small_attackers_first() {
+    let position = Position::from_fen("4k3/8/8/3q4/2P4r/8/8/3QK3 w - - 0 1").unwrap();
+    let mut moves = position.legal_moves_with_promotions();
+    position.order_mvv_lva(&mut moves);
+
+    assert_eq!(moves[0], position.parse_move("cxd5").unwrap());
+    assert_eq!(moves[1], position.parse_move("Qxd5").unwrap());
+    assert_eq!(position.mvv_lva_score(moves[2]), 0); // then the quiet moves
+}
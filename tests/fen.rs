@@ -0,0 +1,302 @@
+use chess::{FenError, PieceColour, Position, Variant};
+
+fn fen_error(fen: &str) -> FenError {
+    Position::from_fen(fen).unwrap_err()
+}
+
+#[test]
+fn missing_fields() {
+    assert_eq!(fen_error(""), FenError::MissingFields { found: 0 });
+    assert_eq!(
+        fen_error("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq"),
+        FenError::MissingFields { found: 3 }
+    );
+}
+
+#[test]
+fn bad_ranks() {
+    assert_eq!(
+        fen_error("8/8/8/8/8/8/8 w - - 0 1"),
+        FenError::BadRankCount {
+            found: 7,
+            position: 13
+        }
+    );
+    assert_eq!(
+        fen_error("8/8/8/8/8/8/8/8/8 w - - 0 1"),
+        FenError::BadRankCount {
+            found: 9,
+            position: 16
+        }
+    );
+
+    // too many squares
+    assert_eq!(
+        fen_error("rnbqkbnrr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1"),
+        FenError::BadRankLength {
+            rank: 8,
+            position: 8
+        }
+    );
+    assert_eq!(
+        fen_error("4k3/8/8/8/8/8/8/4K4 w - - 0 1"),
+        FenError::BadRankLength {
+            rank: 1,
+            position: 18
+        }
+    );
+
+    // too few squares
+    assert_eq!(
+        fen_error("4k3/7/8/8/8/8/8/4K3 w - - 0 1"),
+        FenError::BadRankLength {
+            rank: 7,
+            position: 5
+        }
+    );
+    assert_eq!(
+        fen_error("4k3/8/08/8/8/8/8/4K3 w - - 0 1"),
+        FenError::BadRankLength {
+            rank: 6,
+            position: 6
+        }
+    );
+}
+
+#[test]
+fn unknown_piece() {
+    assert_eq!(
+        fen_error("4k3/8/8/8/8/8/8/4K2X w - - 0 1"),
+        FenError::UnknownPiece {
+            character: 'X',
+            position: 19
+        }
+    );
+    assert_eq!(
+        fen_error("4k3/8/8/8/8/8/8/~3K3 w - - 0 1"),
+        FenError::UnknownPiece {
+            character: '~',
+            position: 16
+        }
+    );
+}
+
+#[test]
+fn bad_side_to_move() {
+    assert_eq!(
+        fen_error("4k3/8/8/8/8/8/8/4K3 x - - 0 1"),
+        FenError::BadSideToMove { position: 20 }
+    );
+}
+
+#[test]
+fn bad_castling() {
+    assert_eq!(
+        fen_error("4k3/8/8/8/8/8/8/4K2R w KX - 0 1"),
+        FenError::BadCastling {
+            character: 'X',
+            position: 24
+        }
+    );
+    assert_eq!(
+        fen_error("4k3/8/8/8/8/8/8/4K2R w E - 0 1"), // rook file can't be the king's
+        FenError::BadCastling {
+            character: 'E',
+            position: 23
+        }
+    );
+}
+
+#[test]
+fn bad_en_passant() {
+    for en_passant in ["e4", "z9", "e"] {
+        assert_eq!(
+            fen_error(&format!("4k3/8/8/8/8/8/8/4K3 w - {} 0 1", en_passant)),
+            FenError::BadEnPassant { position: 24 }
+        );
+    }
+
+    // wrong rank for the side to move
+    assert_eq!(
+        fen_error("4k3/8/8/8/4P3/8/8/4K3 w - e3 0 1"),
+        FenError::BadEnPassant { position: 26 }
+    );
+    // no pawn in front of the square
+    assert_eq!(
+        fen_error("4k3/8/8/8/8/8/3P4/4K3 w - e3 0 1"),
+        FenError::BadEnPassant { position: 26 }
+    );
+    assert_eq!(
+        fen_error("4k3/8/8/8/8/8/8/4K3 b - e3 0 1"),
+        FenError::BadEnPassant { position: 24 }
+    );
+    assert_eq!(
+        fen_error("4k3/8/8/4p3/8/8/8/4K3 b - e6 0 1"),
+        FenError::BadEnPassant { position: 26 }
+    );
+
+    let position = Position::from_fen("4k3/8/8/3pP3/8/8/8/4K3 w - d6 0 1").unwrap();
+    assert_eq!(position.to_fen(), "4k3/8/8/3pP3/8/8/8/4K3 w - d6 0 1");
+}
+
+#[test]
+fn bad_counters() {
+    assert_eq!(
+        fen_error("4k3/8/8/8/8/8/8/4K3 w - - x 1"),
+        FenError::BadCounters { position: 26 }
+    );
+    assert_eq!(
+        fen_error("4k3/8/8/8/8/8/8/4K3 w - - 0 -1"),
+        FenError::BadCounters { position: 28 }
+    );
+    assert_eq!(
+        fen_error("4k3/8/8/8/8/8/8/4K3 w - - 0 0"), // move numbers start at 1
+        FenError::BadCounters { position: 28 }
+    );
+    assert_eq!(
+        fen_error("4k3/8/8/8/8/8/8/4K3 w - - 0 1 7"),
+        FenError::BadCounters { position: 30 }
+    );
+}
+
+#[test]
+fn bad_checks_and_pockets() {
+    assert_eq!(
+        fen_error("4k3/8/8/8/8/8/8/4K3 w - - 4+0 0 1"),
+        FenError::BadChecks { position: 26 }
+    );
+    assert_eq!(
+        fen_error("4k3/8/8/8/8/8/8/4K3 w - - 0 1 +1+x"),
+        FenError::BadChecks { position: 30 }
+    );
+
+    assert_eq!(
+        fen_error("4k3/8/8/8/8/8/8/4K3[pK] w - - 0 1"),
+        FenError::BadPocket { position: 21 }
+    );
+}
+
+#[test]
+fn unreachable_positions() {
+    assert_eq!(
+        fen_error("8/8/8/8/8/8/8/8 w - - 0 1"),
+        FenError::BadKingCount {
+            colour: PieceColour::White,
+            found: 0
+        }
+    );
+    assert_eq!(
+        fen_error("4k3/8/8/8/8/8/8/3KK3 w - - 0 1"),
+        FenError::BadKingCount {
+            colour: PieceColour::White,
+            found: 2
+        }
+    );
+    assert_eq!(
+        fen_error("8/8/8/8/8/8/8/4K3 w - - 0 1"),
+        FenError::BadKingCount {
+            colour: PieceColour::Black,
+            found: 0
+        }
+    );
+    assert!(
+        Position::from_fen_with_variant("8/8/8/8/8/8/8/2KK4 w - - 0 1", Variant::Antichess).is_ok()
+    );
+
+    assert_eq!(
+        fen_error("P3k3/8/8/8/8/8/8/4K2p w - - 0 1"),
+        FenError::PawnOnBackRank { position: 0 }
+    );
+    assert_eq!(
+        fen_error("4k3/8/8/8/8/8/8/4K2p w - - 0 1"),
+        FenError::PawnOnBackRank { position: 19 }
+    );
+
+    assert_eq!(
+        fen_error("4k3/4R3/8/8/8/8/8/6K1 w - - 0 1"),
+        FenError::OpponentInCheck
+    );
+
+    // castling rights need the king and rook at home
+    assert_eq!(
+        fen_error("8/8/8/8/8/8/8/8 w KQkq - 0 1"),
+        FenError::CastlingWithoutPieces {
+            character: 'K',
+            position: 18
+        }
+    );
+    assert_eq!(
+        fen_error("r3k3/8/8/8/8/8/8/R3K2R w KQkq - 0 1"),
+        FenError::CastlingWithoutPieces {
+            character: 'k',
+            position: 27
+        }
+    );
+    assert_eq!(
+        fen_error("4k3/8/8/8/8/8/4K3/R6R w Q - 0 1"),
+        FenError::CastlingWithoutPieces {
+            character: 'Q',
+            position: 24
+        }
+    );
+}
+
+#[test]
+fn errors_say_where() {
+    assert_eq!(
+        fen_error("4k3/8/8/8/8/8/8/4K2X w - - 0 1").to_string(),
+        "unknown piece 'X' (at character 19)"
+    );
+}
+
+#[test]
+fn move_counters_round_trip() {
+    let fen = "r3k2r/8/8/8/8/8/8/R3K2R b KQkq - 37 52";
+    let position = Position::from_fen(fen).unwrap();
+    assert_eq!(
+        (position.halfmove_clock, position.fullmove_number),
+        (37, 52)
+    );
+    assert_eq!(position.to_fen(), fen);
+
+    let four_fields = Position::from_fen("4k3/8/8/8/8/8/8/4K3 w - -").unwrap();
+    assert_eq!(
+        (four_fields.halfmove_clock, four_fields.fullmove_number),
+        (0, 1)
+    );
+    assert_eq!(four_fields.to_fen(), "4k3/8/8/8/8/8/8/4K3 w - - 0 1");
+}
+
+#[test]
+fn moves_update_the_counters() {
+    let start = Position::from_fen("4k3/8/8/3p4/8/8/n3P3/R3K3 w - - 10 20").unwrap();
+    let mut position = start.clone();
+    let mut undo_infos = Vec::new();
+
+    for (text, counters) in [
+        ("Rxa2", (0, 20)), // capture
+        ("Kf7", (1, 21)),  // black moved, next move number
+        ("Ra3", (2, 21)),
+        ("Kf6", (3, 22)),
+        ("e4", (0, 22)), // pawn move
+        ("dxe4", (0, 23)),
+    ] {
+        let piece_move = position.parse_move(text).unwrap();
+        undo_infos.push(position.make_move(piece_move));
+        assert_eq!(
+            (position.halfmove_clock, position.fullmove_number),
+            counters,
+            "{}",
+            text
+        );
+    }
+
+    while let Some(undo_info) = undo_infos.pop() {
+        position.unmake_move(undo_info);
+    }
+    assert_eq!(
+        (position.halfmove_clock, position.fullmove_number),
+        (10, 20)
+    );
+    assert_eq!(position, start);
+}
@@ -0,0 +1,65 @@
+use chess::perft::{divide_hashed, perft, perft_hashed};
+use chess::{Game, HashTable, Position};
+
+const KIWIPETE: &str = "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1";
+
+#[test]
+fn hashed_perft_matches_plain_perft() {
+    let rooks = Position::from_fen("4k3/8/8/8/8/8/8/R3K2R w KQ - 0 1").unwrap();
+    let mut table: HashTable<u64> = HashTable::with_megabytes(4);
+
+    assert_eq!(perft_hashed(&rooks, 5, &mut table), perft(&rooks, 5));
+    assert!(table.stats().hits > 0);
+    assert!(table.stats().hit_rate() > 0.0);
+
+    // a tiny table has to replace entries all the time but must stay correct
+    let kiwipete = Position::from_fen(KIWIPETE).unwrap();
+    let mut tiny_table: HashTable<u64> = HashTable::with_megabytes(0);
+    assert_eq!(tiny_table.capacity(), 2);
+    assert_eq!(perft_hashed(&kiwipete, 3, &mut tiny_table), 97_862);
+    assert!(tiny_table.stats().overwrites > 0);
+
+    let divided: u64 = divide_hashed(&kiwipete, 3, &mut table)
+        .iter()
+        .map(|(_, nodes)| nodes)
+        .sum();
+    assert_eq!(divided, perft(&kiwipete, 3));
+}
+
+#[test]
+fn keeps_the_deepest_result_and_tells_depths_apart() {
+    let mut table: HashTable<u64> = HashTable::with_megabytes(0); // one bucket
+
+    table.store(1, 5, 500);
+    table.store(2, 3, 300); // shallower, goes in the always replaced slot
+    table.store(3, 2, 200); // replaces it
+
+    assert_eq!(table.probe(1, 5), Some(500));
+    assert_eq!(table.probe(1, 4), None);
+    assert_eq!(table.probe(2, 3), None);
+    assert_eq!(table.probe(3, 2), Some(200));
+
+    // the same position stored shallower doesn't push out the deeper result
+    table.store(1, 1, 100);
+    assert_eq!(table.probe(1, 5), Some(500));
+    assert_eq!(table.probe(1, 1), Some(100));
+
+    table.clear();
+    assert_eq!(table.probe(1, 5), None);
+    assert_eq!(table.stats().probes, 1);
+}
+
+#[test]
+fn caches_legal_moves() {
+    let mut game = Game::new(Position::start());
+    let first_look = game.legal_moves();
+
+    let e4 = game.position.parse_move("e4").unwrap();
+    game.make_move(e4);
+    game.legal_moves();
+    game.undo();
+
+    assert_eq!(game.legal_moves(), first_look);
+    assert_eq!(game.move_cache_stats().hits, 1);
+    assert_eq!(game.move_cache_stats().probes, 3);
+}
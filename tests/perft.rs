@@ -0,0 +1,120 @@
+use chess::bitboard::Bitboards;
+use chess::perft::{divide, perft};
+use chess::{MoveFlag, Position, START_FEN};
+
+const KIWIPETE: &str = "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1";
+const POSITION_3: &str = "8/2p5/3p4/KP5r/1R3p1k/8/4P1P1/8 w - - 0 1";
+const POSITION_4: &str = "r3k2r/Pppp1ppp/1b3nbN/nP6/BBP1P3/q4N2/Pp1P2PP/R2Q1RK1 w kq - 0 1";
+const POSITION_4_MIRRORED: &str =
+    "r2q1rk1/pP1p2pp/Q4n2/bbp1p3/Np6/1B3NBn/pPPP1PPP/R3K2R b KQ - 0 1";
+const POSITION_5: &str = "rnbq1k1r/pp1Pbppp/2p5/8/2B5/8/PPP1NnPP/RNBQK2R w KQ - 1 8";
+const POSITION_6: &str = "r4rk1/1pp1qppp/p1np1n2/2b1p1B1/2B1P1b1/P1NP1N2/1PP1QPPP/R4RK1 w - - 0 10";
+
+fn assert_perft(fen: &str, expected_counts: &[u64]) {
+    let position = Position::from_fen(fen).unwrap();
+
+    for (depth, expected) in expected_counts.iter().enumerate() {
+        assert_eq!(
+            perft(&position, depth as u32 + 1),
+            *expected,
+            "perft({}) of {}",
+            depth + 1,
+            fen
+        );
+    }
+}
+
+#[test]
+fn start_position() {
+    assert_perft(START_FEN, &[20, 400, 8902, 197281]);
+}
+
+#[test]
+fn kiwipete() {
+    assert_perft(KIWIPETE, &[48, 2039, 97862]);
+}
+
+#[test]
+fn position_3() {
+    assert_perft(POSITION_3, &[14, 191, 2812, 43238]);
+}
+
+#[test]
+fn position_4() {
+    assert_perft(POSITION_4, &[6, 264, 9467]);
+    assert_perft(POSITION_4_MIRRORED, &[6, 264, 9467]);
+}
+
+#[test]
+fn position_5() {
+    assert_perft(POSITION_5, &[44, 1486, 62379]);
+}
+
+#[test]
+fn position_6() {
+    assert_perft(POSITION_6, &[46, 2079, 89890]);
+}
+
+#[test]
+fn divide_adds_up_to_perft() {
+    let position = Position::from_fen(KIWIPETE).unwrap();
+    let move_counts = divide(&position, 2);
+
+    assert_eq!(move_counts.len(), 48);
+    assert_eq!(
+        move_counts.iter().map(|(_, nodes)| nodes).sum::<u64>(),
+        perft(&position, 2)
+    );
+}
+
+#[test]
+fn no_castling_through_squares_attacked_by_pawns() {
+    // the pawn on e2 attacks d1 and f1 without being able to move there
+    let position = Position::from_fen("4k3/8/8/8/8/8/4p3/R3K2R w KQ - 0 1").unwrap();
+
+    assert!(position
+        .legal_moves()
+        .iter()
+        .all(|legal_move| legal_move.flag != MoveFlag::Castling));
+}
+
+#[test]
+fn no_castling_out_of_check() {
+    let position = Position::from_fen("4k3/8/8/8/8/3n4/8/R3K2R w KQ - 0 1").unwrap();
+
+    assert!(position
+        .legal_moves()
+        .iter()
+        .all(|legal_move| legal_move.flag != MoveFlag::Castling));
+}
+
+fn assert_incremental_state(position: &Position, depth: u32) {
+    // walks the move tree checking make_move kept the hash and bitboards in step with the board
+    assert_eq!(
+        position.hash,
+        position.compute_hash(),
+        "{}",
+        position.to_fen()
+    );
+    assert_eq!(position.bitboards, Bitboards::from_board(&position.board));
+
+    if depth == 0 {
+        return;
+    }
+
+    for legal_move in position.legal_moves_with_promotions() {
+        let mut position_after_move = position.clone();
+        let undo_info = position_after_move.make_move(legal_move);
+        assert_incremental_state(&position_after_move, depth - 1);
+
+        position_after_move.unmake_move(undo_info);
+        assert_eq!(&position_after_move, position, "unmaking {:?}", legal_move);
+    }
+}
+
+#[test]
+fn make_and_unmake_keep_position_in_step() {
+    for fen in [KIWIPETE, POSITION_3, POSITION_4, POSITION_5] {
+        assert_incremental_state(&Position::from_fen(fen).unwrap(), 2);
+    }
+}
@@ -0,0 +1,140 @@
+use chess::{Game, GameResult, MoveParseError, PgnError, PgnTags, PieceColour, Position};
+
+fn play(game: &mut Game, moves: &[&str]) {
+    for text in moves {
+        let piece_move = game.position.parse_move(text).unwrap();
+        game.make_move(piece_move);
+    }
+}
+
+#[test]
+fn exports_tags_moves_and_result() {
+    let mut game = Game::new(Position::start());
+    play(&mut game, &["f3", "e5", "g4", "Qh4"]);
+
+    let tags = PgnTags {
+        white: String::from("Fool"),
+        black: String::from("Say \"Mate\""),
+        ..PgnTags::new()
+    };
+    let pgn = game.to_pgn(&tags);
+
+    assert_eq!(game.result(), GameResult::BlackWins);
+    assert!(pgn.starts_with("[Event \"?\"]\n[Site \"?\"]\n[Date \""));
+    assert!(pgn.contains("[White \"Fool\"]\n[Black \"Say \\\"Mate\\\"\"]\n[Result \"0-1\"]\n\n"));
+    assert!(!pgn.contains("[SetUp"));
+    assert!(pgn.ends_with("\n1. f3 e5 2. g4 Qh4# 0-1\n"));
+}
+
+#[test]
+fn exports_custom_start_and_unfinished_games() {
+    let fen = "4k3/8/8/8/8/8/4P3/4K3 b - - 3 40";
+    let mut game = Game::new(Position::from_fen(fen).unwrap());
+    play(&mut game, &["Kd7", "e4", "Kc6"]);
+
+    let pgn = game.to_pgn(&PgnTags::new());
+
+    assert!(
+        pgn.contains("[Result \"*\"]\n[SetUp \"1\"]\n[FEN \"4k3/8/8/8/8/8/4P3/4K3 b - - 3 40\"]\n")
+    );
+    assert!(pgn.ends_with("\n40... Kd7 41. e4 Kc6 *\n"));
+}
+
+#[test]
+fn wraps_long_move_text() {
+    let mut game = Game::new(Position::start());
+    for _ in 0..10 {
+        play(&mut game, &["Nf3", "Nf6", "Ng1", "Ng8"]);
+    }
+    game.claim_draw();
+
+    let pgn = game.to_pgn(&PgnTags::new());
+    let move_text = pgn.split("\n\n").nth(1).unwrap();
+
+    assert_eq!(game.result(), GameResult::Draw);
+    assert!(move_text.lines().count() > 1);
+    assert!(move_text.lines().all(|line| line.len() <= 80));
+    assert!(move_text.trim_end().ends_with("1/2-1/2"));
+}
+
+#[test]
+fn imports_main_line_past_comments_nags_and_variations() {
+    let pgn = "[Event \"Club \\\"Open\\\"\"]\n[White \"A\"]\n[Black \"B\"]\n\n\
+               1.e4 {open game} e5 $1 2. Nf3 (2. f4 exf4 (2... d5) 3. Nf3) 2... Nc6!? ; comment\n\
+               %escaped line 3. d4\n\
+               3. Bb5 a6 *\n";
+
+    let (game, tags) = Game::from_pgn(pgn).unwrap();
+
+    assert_eq!(tags.event, "Club \"Open\"");
+    assert_eq!(tags.date, "????.??.??");
+    assert_eq!(game.san_moves(), ["e4", "e5", "Nf3", "Nc6", "Bb5", "a6"]);
+}
+
+#[test]
+fn round_trips_exported_games() {
+    let fen = "4k3/8/8/8/8/8/4P3/4K3 b - - 3 40";
+    let mut game = Game::new(Position::from_fen(fen).unwrap());
+    play(&mut game, &["Kd7", "e4", "Kc6"]);
+
+    let (loaded_game, _) = Game::from_pgn(&game.to_pgn(&PgnTags::new())).unwrap();
+
+    assert_eq!(loaded_game.start_position.to_fen(), fen);
+    assert_eq!(loaded_game.position.to_fen(), game.position.to_fen());
+}
+
+#[test]
+fn rejects_bad_pgns() {
+    assert_eq!(
+        Game::from_pgn("1. e4 e5\n2. Nf3 (2. Ke3) Nc6 *").unwrap_err(),
+        PgnError::IllegalMove {
+            text: String::from("Ke3"),
+            line: 2,
+            reason: MoveParseError::IllegalMove,
+        }
+    );
+    assert_eq!(
+        Game::from_pgn("1. e4 (1. d4 *").unwrap_err(),
+        PgnError::UnbalancedVariation { line: 1 }
+    );
+    assert_eq!(
+        Game::from_pgn("[White A]\n1. e4 *").unwrap_err(),
+        PgnError::BadTag { line: 1 }
+    );
+    assert!(matches!(
+        Game::from_pgn("1. e4\n  %not an escape\n*").unwrap_err(),
+        PgnError::IllegalMove { text, line: 2, .. } if text == "%not"
+    ));
+    assert!(matches!(
+        Game::from_pgn("[FEN \"8/8/8 w - -\"]\n*"),
+        Err(PgnError::BadFen(_))
+    ));
+}
+
+#[test]
+fn keeps_the_result() {
+    let (mut game, _) = Game::from_pgn("[Result \"1-0\"]\n1. e4 e5 1-0").unwrap();
+    assert_eq!(game.recorded_result(), Some(GameResult::WhiteWins));
+    assert_eq!(game.result(), GameResult::WhiteWins);
+    game.undo(); // playing on from an earlier move
+    assert_eq!(game.result(), GameResult::Ongoing);
+
+    let (game, _) = Game::from_pgn("1. e4 *").unwrap();
+    assert_eq!(game.recorded_result(), None);
+
+    // resignations survive being saved and loaded
+    let mut game = Game::new(Position::start());
+    play(&mut game, &["d4"]);
+    game.resign(PieceColour::Black);
+    let (loaded, _) = Game::from_pgn(&game.to_pgn(&PgnTags::new())).unwrap();
+    assert_eq!(loaded.result(), GameResult::WhiteWins);
+
+    assert_eq!(
+        Game::from_pgn("[Result \"0-1\"]\n1. e4\n1-0").unwrap_err(),
+        PgnError::ResultMismatch { line: 3 }
+    );
+    assert_eq!(
+        Game::from_pgn("1. f3 e5 2. g4 Qh4# 1-0").unwrap_err(), // black mated
+        PgnError::ResultMismatch { line: 1 }
+    );
+}
@@ -0,0 +1,94 @@
+use chess::{Game, GameResult, Player, PlayerAction, Position, RandomPlayer, ScriptedPlayer};
+
+fn action(player: &mut dyn Player, game: &Game) -> PlayerAction {
+    player.choose_action(game, &game.position.legal_moves_with_promotions())
+}
+
+#[test]
+fn random_player_only_plays_legal_moves() {
+    let mut game = Game::new(Position::start());
+    let mut player = RandomPlayer::with_seed(7);
+
+    for _ in 0..40 {
+        let legal_moves = game.position.legal_moves_with_promotions();
+        if legal_moves.is_empty() {
+            break;
+        }
+
+        match player.choose_action(&game, &legal_moves) {
+            PlayerAction::Move(piece_move) => {
+                assert!(legal_moves.contains(&piece_move));
+                game.make_move(piece_move);
+            }
+            other => panic!("expected a move, got {:?}", other),
+        }
+    }
+
+    // same seed, same choices
+    let first = action(
+        &mut RandomPlayer::with_seed(3),
+        &Game::new(Position::start()),
+    );
+    let second = action(
+        &mut RandomPlayer::with_seed(3),
+        &Game::new(Position::start()),
+    );
+    assert_eq!(first, second);
+}
+
+#[test]
+fn scripted_player_follows_the_game_and_resigns_when_done() {
+    let mut game = Game::new(Position::start());
+    let mut white = ScriptedPlayer::new(vec![String::from("e4"), String::from("Nf3")]);
+    let mut black = ScriptedPlayer::new(vec![String::from("e7e5")]);
+
+    for turn in 0..3 {
+        let player: &mut dyn Player = match turn % 2 {
+            0 => &mut white,
+            _ => &mut black,
+        };
+        match action(player, &game) {
+            PlayerAction::Move(piece_move) => game.make_move(piece_move),
+            other => panic!("expected a move, got {:?}", other),
+        }
+    }
+    assert_eq!(game.san_moves(), ["e4", "e5", "Nf3"]);
+
+    // taking a move back puts the script back a move too
+    game.undo();
+    assert_eq!(
+        action(&mut white, &game),
+        PlayerAction::Move(game.position.parse_move("Nf3").unwrap())
+    );
+    game.redo();
+
+    assert_eq!(action(&mut black, &game), PlayerAction::Resign);
+    game.resign(game.position.colours_turn);
+    assert_eq!(game.result(), GameResult::WhiteWins);
+}
+
+#[test]
+fn scripts_skip_move_numbers() {
+    let script = |name: &str, text: &str| {
+        let file_path = std::env::temp_dir().join(name);
+        std::fs::write(&file_path, text).unwrap();
+        let player = ScriptedPlayer::from_file(file_path.to_str().unwrap()).unwrap();
+        std::fs::remove_file(&file_path).unwrap();
+        player
+    };
+    let mut white = script("rust_chess_white_script.txt", "1.e4 2. Nf3\n3.Bc4 10.\n");
+    let mut black = script("rust_chess_black_script.txt", "1...e5 2...Nc6");
+
+    let mut game = Game::new(Position::start());
+    for turn in 0..5 {
+        let player: &mut dyn Player = match turn % 2 {
+            0 => &mut white,
+            _ => &mut black,
+        };
+        match action(player, &game) {
+            PlayerAction::Move(piece_move) => game.make_move(piece_move),
+            other => panic!("expected a move, got {:?}", other),
+        }
+    }
+    assert_eq!(game.san_moves(), ["e4", "e5", "Nf3", "Nc6", "Bc4"]);
+}
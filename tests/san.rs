@@ -0,0 +1,108 @@
+use chess::{square_index, Move, MoveFlag, MoveParseError, Position};
+
+fn san(fen: &str, start: &str, end: &str, flag: MoveFlag) -> String {
+    let position = Position::from_fen(fen).unwrap();
+
+    position.move_to_san(Move {
+        start: square_index(start).unwrap(),
+        end: square_index(end).unwrap(),
+        flag,
+    })
+}
+
+#[test]
+fn pieces_pawns_and_captures() {
+    let fen = "rnbqkbnr/ppp1pppp/8/3p4/4P3/5N2/PPPP1PPP/RNBQKB1R w KQkq - 0 2";
+
+    assert_eq!(san(fen, "e4", "d5", MoveFlag::None), "exd5");
+    assert_eq!(san(fen, "f3", "e5", MoveFlag::None), "Ne5");
+    assert_eq!(san(fen, "f1", "b5", MoveFlag::None), "Bb5+");
+}
+
+#[test]
+fn disambiguation() {
+    let fen = "4k3/8/8/8/8/1N3N2/8/R3K2R w - - 0 1";
+
+    assert_eq!(san(fen, "b3", "d2", MoveFlag::None), "Nbd2");
+    assert_eq!(
+        san(
+            "4k3/8/8/8/8/8/8/R4RK1 w - - 0 1",
+            "a1",
+            "d1",
+            MoveFlag::None
+        ),
+        "Rad1"
+    );
+    assert_eq!(
+        san(
+            "4k3/8/8/8/R7/8/8/R3K3 w - - 0 1",
+            "a4",
+            "a2",
+            MoveFlag::None
+        ),
+        "R4a2"
+    );
+    assert_eq!(
+        san(
+            "6k1/8/8/8/Q2Q4/8/8/Q3K3 w - - 0 1",
+            "a4",
+            "d1",
+            MoveFlag::None
+        ),
+        "Qa4d1"
+    );
+}
+
+#[test]
+fn castling_promotion_and_mate() {
+    let fen = "r3k2r/1P6/8/8/8/8/8/R3K2R w KQkq - 0 1";
+
+    assert_eq!(san(fen, "e1", "g1", MoveFlag::Castling), "O-O");
+    assert_eq!(san(fen, "e1", "c1", MoveFlag::Castling), "O-O-O");
+    assert_eq!(san(fen, "b7", "a8", MoveFlag::QueenPromo), "bxa8=Q+");
+    assert_eq!(
+        san("7k/8/6K1/8/8/8/8/5R2 w - - 0 1", "f1", "f8", MoveFlag::None),
+        "Rf8#"
+    );
+}
+
+fn parse(fen: &str, text: &str) -> Result<String, MoveParseError> {
+    let position = Position::from_fen(fen).unwrap();
+
+    position
+        .parse_move(text)
+        .map(|legal_move| legal_move.to_long_algebraic())
+}
+
+#[test]
+fn parses_standard_and_long_algebraic() {
+    let fen = "r3k2r/1P6/8/3p4/4P3/1N3N2/8/R3K2R w KQkq - 0 1";
+
+    assert_eq!(parse(fen, "Nfd4").unwrap(), "f3d4");
+    assert_eq!(parse(fen, "exd5").unwrap(), "e4d5");
+    assert_eq!(parse(fen, "e5").unwrap(), "e4e5");
+    assert_eq!(parse(fen, "O-O").unwrap(), "e1g1");
+    assert_eq!(parse(fen, "0-0-0").unwrap(), "e1c1");
+    assert_eq!(parse(fen, "bxa8=Q+").unwrap(), "b7a8q");
+    assert_eq!(parse(fen, "b8N").unwrap(), "b7b8n");
+    assert_eq!(parse(fen, "b8=q").unwrap(), "b7b8q");
+    assert_eq!(parse(fen, "bxa8=b").unwrap(), "b7a8b"); // lowercase after '=' is never a file
+    assert_eq!(parse(fen, "e4e5").unwrap(), "e4e5");
+    assert_eq!(parse(fen, "b7b8q").unwrap(), "b7b8q");
+}
+
+#[test]
+fn explains_bad_moves() {
+    let fen = "r3k2r/1P6/8/3p4/4P3/1N3N2/8/R3K2R w KQkq - 0 1";
+
+    assert_eq!(parse(fen, "hello"), Err(MoveParseError::BadNotation));
+    assert_eq!(parse(fen, "Qd1"), Err(MoveParseError::IllegalMove));
+    assert_eq!(parse(fen, "b8"), Err(MoveParseError::PromotionNeeded));
+    assert_eq!(
+        parse(fen, "Nd4"),
+        Err(MoveParseError::AmbiguousMove(vec![
+            String::from("Nbd4"),
+            String::from("Nfd4")
+        ]))
+    );
+}
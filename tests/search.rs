@@ -0,0 +1,87 @@
+use std::sync::atomic::AtomicBool;
+use std::time::Duration;
+
+use chess::search::{search, search_game};
+use chess::{Game, Position, SearchLimits};
+
+fn best_move(fen: &str, depth: u32) -> String {
+    let position = Position::from_fen(fen).unwrap();
+    let limits = SearchLimits {
+        depth: Some(depth),
+        ..SearchLimits::default()
+    };
+
+    let result = search(&position, &limits, &AtomicBool::new(false), |_| ());
+    result.best_move.unwrap().to_long_algebraic()
+}
+
+#[test]
+fn finds_mates_and_material() {
+    assert_eq!(best_move("6k1/5ppp/8/8/8/8/8/R5K1 w - - 0 1", 2), "a1a8");
+    assert_eq!(best_move("4k3/8/8/3q4/8/8/8/3RK3 w - - 0 1", 2), "d1d5");
+    assert_eq!(best_move("2k5/8/8/8/8/8/5R2/4R1K1 w - - 0 1", 5), "f2f7");
+}
+
+#[test]
+fn looks_at_every_way_out_of_check() {
+    // the knight check forks king and queen, black can't stand pat in check
+    assert_eq!(best_move("2q1k3/8/8/1N6/8/8/8/6K1 w - - 0 1", 1), "b5d6");
+}
+
+#[test]
+fn reports_every_depth_with_a_principal_variation() {
+    let position = Position::from_fen("2k5/8/8/8/8/8/5R2/4R1K1 w - - 0 1").unwrap();
+    let limits = SearchLimits {
+        depth: Some(5),
+        ..SearchLimits::default()
+    };
+    let mut depths: Vec<u32> = Vec::new();
+
+    let result = search(&position, &limits, &AtomicBool::new(false), |result| {
+        depths.push(result.depth)
+    });
+
+    assert_eq!(depths, [1, 2, 3, 4, 5]);
+
+    // the mate takes five plies, so deepening stops there however deep it was allowed to go
+    let mut last_depth = 0;
+    let deeper = SearchLimits {
+        depth: Some(9),
+        ..SearchLimits::default()
+    };
+    search(&position, &deeper, &AtomicBool::new(false), |result| {
+        last_depth = result.depth
+    });
+    assert_eq!(last_depth, 5);
+    assert_eq!(result.mate_in(), Some(3));
+    assert_eq!(result.principal_variation.len(), 5);
+    assert_eq!(
+        result.best_move,
+        result.principal_variation.first().copied()
+    );
+}
+
+#[test]
+fn stops_when_told() {
+    let stopped = AtomicBool::new(true);
+    let result = search(
+        &Position::start(),
+        &SearchLimits::default(),
+        &stopped,
+        |_| (),
+    );
+    assert!(result.best_move.is_some()); // still has something to play
+
+    let limits = SearchLimits {
+        move_time: Some(Duration::from_millis(50)),
+        ..SearchLimits::default()
+    };
+    let result = search_game(
+        &Game::new(Position::start()),
+        &limits,
+        &AtomicBool::new(false),
+        |_| (),
+    );
+    assert!(result.best_move.is_some());
+    assert!(result.time < Duration::from_secs(1));
+}
@@ -0,0 +1,40 @@
+use chess::{GameStatus, PieceColour, Position};
+
+#[test]
+fn stalemate_is_not_checkmate() {
+    for fen in [
+        "7k/5Q2/6K1/8/8/8/8/8 b - - 0 1",
+        "k7/8/1Q6/8/8/8/8/7K b - - 0 1",
+        "5k2/5P2/5K2/8/8/8/8/8 b - - 0 1", // pawn and king take every square
+    ] {
+        let position = Position::from_fen(fen).unwrap();
+        assert!(position.legal_moves().is_empty(), "{}", fen);
+        assert!(!position.is_in_check());
+        assert_eq!(position.status(), GameStatus::Stalemate);
+        assert!(position.is_stalemate());
+        assert!(!position.is_checkmate());
+    }
+}
+
+#[test]
+fn checkmate_names_the_winner() {
+    let fools_mate =
+        Position::from_fen("rnb1kbnr/pppp1ppp/8/4p3/6Pq/5P2/PPPPP2P/RNBQKBNR w KQkq - 1 3")
+            .unwrap();
+    assert_eq!(
+        fools_mate.status(),
+        GameStatus::Checkmate(PieceColour::Black)
+    );
+    assert!(fools_mate.is_checkmate());
+    assert!(!fools_mate.is_stalemate());
+
+    let back_rank = Position::from_fen("R5k1/5ppp/8/8/8/8/8/6K1 b - - 0 1").unwrap();
+    assert_eq!(
+        back_rank.status(),
+        GameStatus::Checkmate(PieceColour::White)
+    );
+
+    // in check with a way out
+    let check = Position::from_fen("R5k1/5pp1/8/8/8/8/8/6K1 b - - 0 1").unwrap();
+    assert_eq!(check.status(), GameStatus::Ongoing { in_check: true });
+}
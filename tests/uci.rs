@@ -0,0 +1,89 @@
+use std::io::{self, Write};
+use std::sync::{Arc, Mutex};
+
+use chess::uci;
+
+#[derive(Clone, Default)]
+struct SharedBuffer(Arc<Mutex<Vec<u8>>>);
+
+impl Write for SharedBuffer {
+    fn write(&mut self, bytes: &[u8]) -> io::Result<usize> {
+        self.0.lock().unwrap().write(bytes)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+fn run(commands: &str) -> Vec<String> {
+    let output = SharedBuffer::default();
+    uci::run(commands.as_bytes(), output.clone());
+
+    let bytes = output.0.lock().unwrap().clone();
+    String::from_utf8(bytes)
+        .unwrap()
+        .lines()
+        .map(String::from)
+        .collect()
+}
+
+#[test]
+fn handshake() {
+    let lines = run("uci\nisready\nquit\n");
+
+    assert!(lines[0].starts_with("id name "));
+    assert!(lines[1].starts_with("id author "));
+    assert_eq!(
+        lines[2..],
+        [
+            "option name UCI_Chess960 type check default false",
+            "uciok",
+            "readyok"
+        ]
+    );
+}
+
+#[test]
+fn chess960_castling_is_king_takes_rook() {
+    let castle = "position fen 4k3/8/8/8/8/8/8/4K2R w K - 0 1 moves e1h1 e8d8\nisready\nquit\n";
+    assert_eq!(
+        run(castle),
+        [
+            "info string can't play 'e1h1': no legal move matches",
+            "readyok"
+        ]
+    );
+
+    let chess960 = format!("setoption name UCI_Chess960 value true\n{}", castle);
+    assert_eq!(run(&chess960), ["readyok"]);
+}
+
+#[test]
+fn answers_go_with_a_legal_move() {
+    // only black's king can move after white castles
+    let lines = run("position fen 4k3/8/8/8/8/8/8/4K2R w K - 0 1 moves e1g1\ngo depth 1\nquit\n");
+    let best_move = lines.last().unwrap();
+
+    assert!(lines.iter().any(|line| line.starts_with("info depth ")));
+    assert!([
+        "bestmove e8d8",
+        "bestmove e8d7",
+        "bestmove e8e7",
+        "bestmove e8f7",
+        "bestmove e8f8"
+    ]
+    .contains(&best_move.as_str()));
+
+    // checkmated, nothing to play
+    let lines = run("position startpos moves f2f3 e7e5 g2g4 d8h4\ngo movetime 10\n");
+    assert_eq!(lines.last().unwrap(), "bestmove 0000");
+}
+
+#[test]
+fn reports_bad_commands() {
+    let lines = run("position startpos moves e2e5\nposition fen 8/8 w\nfly\ngo sideways\n");
+
+    assert_eq!(lines.len(), 4);
+    assert!(lines.iter().all(|line| line.starts_with("info string ")));
+}
@@ -0,0 +1,131 @@
+use chess::perft::perft;
+use chess::{
+    DrawReason, Game, GameResult, GameStatus, PgnTags, PieceColour, Position, Variant,
+    VariantOutcome,
+};
+
+fn play(position: &mut Position, moves: &[&str]) {
+    for text in moves {
+        let piece_move = position.parse_move(text).unwrap();
+        position.make_move(piece_move);
+        assert_eq!(position.hash, position.compute_hash());
+    }
+}
+
+#[test]
+fn variant_names() {
+    for variant in Variant::ALL {
+        assert_eq!(Variant::from_name(variant.name()), Some(variant));
+    }
+    assert_eq!(Variant::from_name("threecheck"), Some(Variant::ThreeCheck));
+    assert_eq!(Variant::from_name("bughouse"), None);
+}
+
+#[test]
+fn king_of_the_hill() {
+    let mut position =
+        Position::from_fen_with_variant("4k3/8/8/8/8/3K4/8/8 w - - 0 1", Variant::KingOfTheHill)
+            .unwrap();
+    assert!(!position.is_insufficient_material()); // bare kings can still race to the hill
+
+    play(&mut position, &["Ke4"]);
+    assert_eq!(
+        position.variant_outcome(),
+        Some(VariantOutcome::Win(PieceColour::White))
+    );
+    assert_eq!(
+        position.status(),
+        GameStatus::VariantWin(PieceColour::White)
+    );
+}
+
+#[test]
+fn three_check() {
+    let mut position = Variant::ThreeCheck.start_position();
+    assert_eq!(
+        position.to_fen(),
+        "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 3+3 0 1"
+    );
+    assert_eq!(perft(&position, 3), 8902);
+
+    play(&mut position, &["e4", "e5", "Bc4", "Nc6", "Bxf7+"]);
+    assert_eq!(position.checks_given, (1, 0));
+    assert!(position.to_fen().contains(" - 2+3 0 3"));
+
+    // checks given at the end of the fen (+white+black) read the same
+    let same =
+        Position::from_fen("r1bqkbnr/pppp1Bpp/2n5/4p3/4P3/8/PPPP1PPP/RNBQK1NR b KQkq - 0 3 +1+0")
+            .unwrap();
+    assert_eq!(same.variant, Variant::ThreeCheck);
+    assert_eq!(same.hash, position.hash);
+
+    play(&mut position, &["Kxf7", "Qh5+", "g6", "Qxg6+"]);
+    assert_eq!(position.checks_given, (3, 0));
+    assert_eq!(
+        position.status(),
+        GameStatus::VariantWin(PieceColour::White)
+    );
+
+    assert!(Position::from_fen("4k3/8/8/8/8/8/8/4K3 w - - 4+3 0 1").is_err());
+}
+
+#[test]
+fn racing_kings() {
+    let start = Variant::RacingKings.start_position();
+    assert_eq!(perft(&start, 1), 21);
+    assert_eq!(perft(&start, 2), 421);
+    assert_eq!(perft(&start, 3), 11264);
+
+    // the rook can't check the king
+    let position =
+        Position::from_fen_with_variant("k7/8/8/8/8/8/8/1R5K w - - 0 1", Variant::RacingKings)
+            .unwrap();
+    assert!(position.parse_move("Ra1").is_err());
+    assert!(position.parse_move("Rb2").is_ok());
+
+    // white got there first and black can't follow
+    let mut position =
+        Position::from_fen_with_variant("8/6K1/k7/8/8/8/8/8 w - - 0 1", Variant::RacingKings)
+            .unwrap();
+    play(&mut position, &["Kg8"]);
+    assert_eq!(
+        position.status(),
+        GameStatus::VariantWin(PieceColour::White)
+    );
+
+    // black can draw by following, but loses if it doesn't
+    let mut position =
+        Position::from_fen_with_variant("8/k5K1/8/8/8/8/8/8 w - - 0 1", Variant::RacingKings)
+            .unwrap();
+    play(&mut position, &["Kg8"]);
+    assert_eq!(position.status(), GameStatus::Ongoing { in_check: false });
+    play(&mut position, &["Kb6"]);
+    assert_eq!(
+        position.status(),
+        GameStatus::VariantWin(PieceColour::White)
+    );
+
+    let mut position =
+        Position::from_fen_with_variant("8/k5K1/8/8/8/8/8/8 w - - 0 1", Variant::RacingKings)
+            .unwrap();
+    play(&mut position, &["Kg8", "Ka8"]);
+    assert_eq!(position.status(), GameStatus::Draw(DrawReason::VariantRule));
+}
+
+#[test]
+fn pgn_keeps_the_variant() {
+    let mut game = Game::new(Variant::RacingKings.start_position());
+    for move_text in ["Kh3", "Ka3"] {
+        let piece_move = game.position.parse_move(move_text).unwrap();
+        game.make_move(piece_move);
+    }
+
+    let pgn = game.to_pgn(&PgnTags::new());
+    assert!(pgn.contains("[Variant \"Racing Kings\"]"));
+    assert!(!pgn.contains("[FEN"));
+
+    let (loaded, _) = Game::from_pgn(&pgn).unwrap();
+    assert_eq!(loaded.position.variant, Variant::RacingKings);
+    assert_eq!(loaded.moves(), game.moves());
+    assert_eq!(loaded.result(), GameResult::Ongoing);
+}